@@ -1,11 +1,12 @@
 use indoc::indoc;
 use itertools::Itertools;
 use proc_macro::TokenStream;
-use quote::{format_ident, quote};
+use quote::{format_ident, quote, ToTokens};
 use syn::{
-    parse::Parse, parse_macro_input, punctuated::Punctuated, spanned::Spanned, Expr, ExprLit,
-    ExprPath, ExprRange, FnArg, Ident, ImplItem, ItemEnum, ItemFn, ItemImpl, ItemStruct, Lit,
-    LitChar, LitStr, Meta, MetaNameValue, PatType, RangeLimits, Token, TypePath,
+    ext::IdentExt, parse::Parse, parse_macro_input, punctuated::Punctuated, spanned::Spanned,
+    Attribute, Expr, ExprArray, ExprLit, ExprPath, ExprRange, Fields, FnArg, Ident, ImplItem,
+    ItemEnum, ItemFn, ItemImpl, ItemStruct, Lit, LitChar, LitStr, Meta, MetaNameValue, PatType,
+    RangeLimits, Token, Type, TypePath,
 };
 
 // TODO: Use explicit trait methods in all quote! macros.
@@ -28,17 +29,171 @@ enum CommandPath {
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 enum Autocomplete {
-    DefaultName,
+    /// Keeps the `autocomplete` attribute for error messages.
+    DefaultName(Ident),
     CustomName(Ident),
 }
 
-// TODO: Consider implementing syn's Parse trait for this
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+enum Scope {
+    Global,
+    Connected,
+    Only(Vec<u64>),
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+enum AutoDefer {
+    Public,
+    Ephemeral,
+}
 
-#[derive(Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
-struct SlashAttributes<'a> {
-    default: Option<&'a Ident>,
-    rename: Option<CommandPath>,
+/// The attributes of `#[slash]`, `#[user_command]` and `#[message_command]`.
+///
+/// Errors in the values of attributes are collected instead of failing right away, so that all of
+/// them can be reported at once. Unknown attributes are kept as is, since each macro lists its own
+/// set of available attributes.
+#[derive(Default)]
+struct CommandAttributes {
+    default: Option<Ident>,
+    rename: Option<LitStr>,
     autocomplete: Option<Autocomplete>,
+    permissions: Option<Vec<Ident>>,
+    dm: Option<bool>,
+    cooldown: Option<u64>,
+    per: Option<Ident>,
+    scope: Option<Scope>,
+    auto_defer: Option<AutoDefer>,
+    unknown: Vec<Meta>,
+    errors: Vec<TokenStream>,
+}
+
+impl Parse for CommandAttributes {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut attributes = Self::default();
+        for meta in Punctuated::<Meta, Token![,]>::parse_terminated(input)? {
+            if let Err(error) = attributes.add(meta) {
+                attributes.errors.push(error);
+            }
+        }
+        Ok(attributes)
+    }
+}
+
+impl CommandAttributes {
+    fn add(&mut self, meta: Meta) -> Result<(), TokenStream> {
+        match &meta {
+            Meta::Path(path) if path.is_ident("default") => {
+                self.default = path.get_ident().cloned();
+            }
+            Meta::Path(path) if path.is_ident("autocomplete") => {
+                self.autocomplete = path.get_ident().cloned().map(Autocomplete::DefaultName);
+            }
+            Meta::Path(path) if path.is_ident("auto_defer") => {
+                ensure_unset(&self.auto_defer, &meta, multiple_auto_defers)?;
+                self.auto_defer = Some(AutoDefer::Public);
+            }
+            Meta::NameValue(MetaNameValue { path, value, .. }) => {
+                let Some(ident) = path.get_ident() else {
+                    self.unknown.push(meta);
+                    return Ok(());
+                };
+                match ident.to_string().as_str() {
+                    "rename" => {
+                        ensure_unset(&self.rename, &meta, multiple_renames)?;
+                        let Expr::Lit(ExprLit {
+                            lit: Lit::Str(lit_str),
+                            ..
+                        }) = value
+                        else {
+                            return Err(invalid_rename_literal(value));
+                        };
+                        self.rename = Some(lit_str.clone());
+                    }
+                    "autocomplete" => {
+                        ensure_unset(&self.autocomplete, &meta, multiple_autocompletes)?;
+                        let Expr::Lit(ExprLit {
+                            lit: Lit::Str(lit_str),
+                            ..
+                        }) = value
+                        else {
+                            return Err(invalid_autocomplete_ident(value));
+                        };
+                        let ident = lit_str
+                            .parse_with(syn::Ident::parse)
+                            .map_err(|_| invalid_autocomplete_ident(value))?;
+                        self.autocomplete = Some(Autocomplete::CustomName(ident));
+                    }
+                    "permissions" => {
+                        ensure_unset(&self.permissions, &meta, multiple_permissions)?;
+                        self.permissions = Some(parse_permissions(value)?);
+                    }
+                    "dm" => {
+                        ensure_unset(&self.dm, &meta, multiple_dms)?;
+                        self.dm = Some(parse_dm(value)?);
+                    }
+                    "cooldown" => {
+                        ensure_unset(&self.cooldown, &meta, multiple_cooldowns)?;
+                        self.cooldown = Some(parse_duration(value)?);
+                    }
+                    "per" => {
+                        ensure_unset(&self.per, &meta, multiple_cooldown_scopes)?;
+                        self.per = Some(parse_cooldown_scope(value)?);
+                    }
+                    "scope" => {
+                        ensure_unset(&self.scope, &meta, multiple_scopes)?;
+                        self.scope = Some(parse_scope(value)?);
+                    }
+                    "auto_defer" => {
+                        ensure_unset(&self.auto_defer, &meta, multiple_auto_defers)?;
+                        self.auto_defer = Some(parse_auto_defer(value)?);
+                    }
+                    _ => self.unknown.push(meta),
+                }
+            }
+            _ => self.unknown.push(meta),
+        }
+        Ok(())
+    }
+
+    /// The permissions, cooldown, scope and auto_defer of the command, in that order.
+    fn settings(
+        &self,
+        errors: &mut Vec<TokenStream>,
+    ) -> (
+        impl quote::ToTokens,
+        impl quote::ToTokens,
+        impl quote::ToTokens,
+        impl quote::ToTokens,
+    ) {
+        (
+            command_permissions(self.permissions.as_deref(), self.dm),
+            command_cooldown(self.cooldown, self.per.clone(), errors),
+            command_scope(self.scope.as_ref()),
+            command_auto_defer(self.auto_defer),
+        )
+    }
+}
+
+/// Fails if an attribute that can only be specified once was already set.
+fn ensure_unset<T>(
+    slot: &Option<T>,
+    meta: &Meta,
+    multiple: fn(&Meta) -> TokenStream,
+) -> Result<(), TokenStream> {
+    match slot {
+        Some(_) => Err(multiple(meta)),
+        None => Ok(()),
+    }
+}
+
+/// Renames the handler to `__name`, returning the original name and the new one.
+///
+/// The original name is taken by the generated function that creates the command.
+fn rename_handler(item_fn: &mut ItemFn) -> (Ident, Ident) {
+    let name = item_fn.sig.ident.clone();
+    let impl_name = format_ident!("__{name}");
+    item_fn.sig.ident = impl_name.clone();
+    (name, impl_name)
 }
 
 trait CommandString: Spanned {
@@ -119,6 +274,16 @@ fn invalid_attribute(span: &impl Spanned) -> TokenStream {
             available attributes are
                 `default`
                 `rename = "..."`
+                `autocomplete`
+                `autocomplete = "..."`
+                `permissions = "..."`
+                `dm = true|false`
+                `cooldown = "..."`
+                `per = "user|guild|channel|global"`
+                `scope = "global|connected"`
+                `scope = [guild_id, ...]`
+                `auto_defer`
+                `auto_defer = "ephemeral"`
         "#},
     )
     .into_compile_error()
@@ -158,96 +323,1105 @@ fn invalid_autocomplete_ident(span: &impl Spanned) -> TokenStream {
         .into()
 }
 
+fn multiple_permissions(span: &impl Spanned) -> TokenStream {
+    syn::Error::new(span.span(), "permissions can only be specified once")
+        .into_compile_error()
+        .into()
+}
+
+fn multiple_dms(span: &impl Spanned) -> TokenStream {
+    syn::Error::new(span.span(), "dm can only be specified once")
+        .into_compile_error()
+        .into()
+}
+
+fn multiple_cooldowns(span: &impl Spanned) -> TokenStream {
+    syn::Error::new(span.span(), "cooldown can only be specified once")
+        .into_compile_error()
+        .into()
+}
+
+fn multiple_cooldown_scopes(span: &impl Spanned) -> TokenStream {
+    syn::Error::new(span.span(), "per can only be specified once")
+        .into_compile_error()
+        .into()
+}
+
+fn multiple_scopes(span: &impl Spanned) -> TokenStream {
+    syn::Error::new(span.span(), "scope can only be specified once")
+        .into_compile_error()
+        .into()
+}
+
+fn multiple_auto_defers(span: &impl Spanned) -> TokenStream {
+    syn::Error::new(span.span(), "auto_defer can only be specified once")
+        .into_compile_error()
+        .into()
+}
+
+fn invalid_parameter_attribute(span: &impl Spanned, expected: &str) -> TokenStream {
+    syn::Error::new(span.span(), format!("expected `{expected}`"))
+        .into_compile_error()
+        .into()
+}
+
+fn multiple_parameter_attributes(attr: &Attribute) -> TokenStream {
+    let name = attr.path().to_token_stream();
+    syn::Error::new(attr.span(), format!("`{name}` can only be specified once"))
+        .into_compile_error()
+        .into()
+}
+
+/// Joins the first paragraph of a doc comment into a single line.
+fn doc_description(attrs: &[Attribute]) -> Option<String> {
+    let doc = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(MetaNameValue {
+                value:
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(lit_str),
+                        ..
+                    }),
+                ..
+            }) => Some(lit_str.value()),
+            _ => None,
+        })
+        .join("\n");
+
+    let description = doc
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty())
+        .take_while(|line| !line.is_empty())
+        .join(" ");
+
+    (!description.is_empty()).then_some(description)
+}
+
+/// Attributes on a single parameter of a `#[slash]` function or field of `#[derive(CommandOptions)]`.
+#[derive(Default)]
+struct ParameterAttributes {
+    description: Option<String>,
+    rename: Option<LitStr>,
+    min: Option<Expr>,
+    max: Option<Expr>,
+    min_length: Option<Expr>,
+    max_length: Option<Expr>,
+    channel_types: Option<Vec<Ident>>,
+    autocomplete: Option<Ident>,
+    flatten: Option<Attribute>,
+    subcommands: Option<Attribute>,
+}
+
+impl ParameterAttributes {
+    fn has_option_attributes(&self) -> bool {
+        self.rename.is_some()
+            || self.min.is_some()
+            || self.max.is_some()
+            || self.min_length.is_some()
+            || self.max_length.is_some()
+            || self.channel_types.is_some()
+            || self.autocomplete.is_some()
+    }
+
+    fn constraints(&self) -> impl quote::ToTokens {
+        let bound = |bound: &Option<Expr>| match bound {
+            Some(bound) => quote! {
+                ::std::option::Option::Some(
+                    <::tranquil::resolve::OptionBound as ::std::convert::From<_>>::from(#bound)
+                )
+            },
+            None => quote! { ::std::option::Option::None },
+        };
+        let length = |length: &Option<Expr>| match length {
+            Some(length) => quote! { ::std::option::Option::Some(#length) },
+            None => quote! { ::std::option::Option::None },
+        };
+
+        let min = bound(&self.min);
+        let max = bound(&self.max);
+        let min_length = length(&self.min_length);
+        let max_length = length(&self.max_length);
+        let channel_types = match &self.channel_types {
+            Some(channel_types) => quote! {
+                ::std::option::Option::Some(&[#(
+                    ::tranquil::serenity::model::channel::ChannelType::#channel_types
+                ),*])
+            },
+            None => quote! { ::std::option::Option::None },
+        };
+
+        quote! {
+            ::tranquil::resolve::OptionConstraints {
+                min: #min,
+                max: #max,
+                min_length: #min_length,
+                max_length: #max_length,
+                channel_types: #channel_types,
+            }
+        }
+    }
+}
+
+fn set_parameter_attribute<T>(
+    slot: &mut Option<T>,
+    value: T,
+    attr: &Attribute,
+    errors: &mut Vec<TokenStream>,
+) {
+    if slot.is_some() {
+        errors.push(multiple_parameter_attributes(attr));
+    } else {
+        *slot = Some(value);
+    }
+}
+
+/// Removes all attributes that are handled by `#[slash]` from a parameter, since none of them
+/// would be allowed on parameters otherwise.
+///
+/// An explicit `#[description]` takes precedence over a doc comment.
+fn take_parameter_attributes(
+    attrs: &mut Vec<Attribute>,
+    errors: &mut Vec<TokenStream>,
+) -> ParameterAttributes {
+    let doc = doc_description(attrs);
+    let mut attributes = ParameterAttributes::default();
+    attrs.retain(|attr| {
+        let Some(ident) = attr.path().get_ident() else {
+            return true;
+        };
+        let name_value = match &attr.meta {
+            Meta::NameValue(MetaNameValue { value, .. }) => Some(value),
+            _ => None,
+        };
+        let lit_str = match name_value {
+            Some(Expr::Lit(ExprLit {
+                lit: Lit::Str(lit_str),
+                ..
+            })) => Some(lit_str),
+            _ => None,
+        };
+
+        if ident == "doc" {
+            // Already read by `doc_description` above.
+            return false;
+        }
+
+        if ident == "description" {
+            match lit_str {
+                Some(lit_str) => set_parameter_attribute(
+                    &mut attributes.description,
+                    lit_str.value(),
+                    attr,
+                    errors,
+                ),
+                None => errors.push(invalid_parameter_attribute(
+                    attr,
+                    r#"#[description = "..."]"#,
+                )),
+            }
+        } else if ident == "rename" {
+            match lit_str {
+                Some(lit_str) => {
+                    set_parameter_attribute(&mut attributes.rename, lit_str.clone(), attr, errors)
+                }
+                None => errors.push(invalid_parameter_attribute(attr, r#"#[rename = "..."]"#)),
+            }
+        } else if ["min", "max", "min_length", "max_length"]
+            .iter()
+            .any(|name| ident == name)
+        {
+            let slot = match ident.to_string().as_str() {
+                "min" => &mut attributes.min,
+                "max" => &mut attributes.max,
+                "min_length" => &mut attributes.min_length,
+                _ => &mut attributes.max_length,
+            };
+            match name_value {
+                Some(value) => set_parameter_attribute(slot, value.clone(), attr, errors),
+                None => errors.push(invalid_parameter_attribute(
+                    attr,
+                    &format!("#[{ident} = ...]"),
+                )),
+            }
+        } else if ident == "channel_types" {
+            match attr.parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated) {
+                Ok(channel_types) => set_parameter_attribute(
+                    &mut attributes.channel_types,
+                    channel_types.into_iter().collect(),
+                    attr,
+                    errors,
+                ),
+                Err(_) => errors.push(invalid_parameter_attribute(
+                    attr,
+                    "#[channel_types(Text, Voice, ...)]",
+                )),
+            }
+        } else if ident == "flatten" || ident == "subcommands" {
+            let slot = if ident == "flatten" {
+                &mut attributes.flatten
+            } else {
+                &mut attributes.subcommands
+            };
+            match attr.meta {
+                Meta::Path(_) => set_parameter_attribute(slot, attr.clone(), attr, errors),
+                _ => errors.push(invalid_parameter_attribute(attr, &format!("#[{ident}]"))),
+            }
+        } else if ident == "autocomplete" {
+            let autocompleter = match name_value {
+                Some(Expr::Path(ExprPath { path, .. })) => path.get_ident().cloned(),
+                _ => lit_str.and_then(|lit_str| lit_str.parse_with(syn::Ident::parse).ok()),
+            };
+            match autocompleter {
+                Some(autocompleter) => set_parameter_attribute(
+                    &mut attributes.autocomplete,
+                    autocompleter,
+                    attr,
+                    errors,
+                ),
+                None => errors.push(invalid_parameter_attribute(
+                    attr,
+                    "#[autocomplete = autocompleter]",
+                )),
+            }
+        } else {
+            return true;
+        }
+        false
+    });
+    attributes.description = attributes.description.or(doc);
+
+    let multiple_options = [&attributes.flatten, &attributes.subcommands]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    match multiple_options.as_slice() {
+        [] => {}
+        [attr] => {
+            if attributes.has_option_attributes() {
+                errors.push(multiple_options_with_option_attributes(attr));
+            }
+        }
+        [_, attr, ..] => errors.push(multiple_options_with_option_attributes(attr)),
+    }
+
+    attributes
+}
+
+fn multiple_options_with_option_attributes(attr: &Attribute) -> TokenStream {
+    let name = attr.path().to_token_stream();
+    syn::Error::new(
+        attr.span(),
+        format!("`{name}` cannot be combined with attributes of individual options"),
+    )
+    .into_compile_error()
+    .into()
+}
+
+/// A parameter of a `#[slash]` function or a field of a `#[derive(CommandOptions)]` struct.
+///
+/// Turns into a single option, unless it is marked with `#[flatten]`.
+struct OptionParameter<'a, B> {
+    binding: &'a B,
+    ty: &'a Type,
+    name: LitStr,
+    attributes: ParameterAttributes,
+}
+
+impl<B> OptionParameter<'_, B> {
+    fn is_flattened(&self) -> bool {
+        self.attributes.flatten.is_some()
+    }
+}
+
+/// The names of all options, used to find them in the order of the parameters.
+fn option_names<B>(parameters: &[OptionParameter<B>]) -> impl quote::ToTokens {
+    let any_flattened = parameters.iter().any(OptionParameter::is_flattened);
+    let names = parameters.iter().map(|parameter| {
+        let ty = parameter.ty;
+        let name = &parameter.name;
+        if parameter.is_flattened() {
+            quote! { <#ty as ::tranquil::resolve::CommandOptions>::option_names() }
+        } else if any_flattened {
+            quote! { ::std::vec![#name] }
+        } else {
+            quote! { #name }
+        }
+    });
+
+    if any_flattened {
+        quote! { [#(#names),*].concat() }
+    } else {
+        quote! { ::std::vec![#(#names),*] }
+    }
+}
+
+/// The options of all parameters, with flattened ones expanded in place.
+///
+/// Names and descriptions are localized later, once the path of the command is known.
+fn command_options<B>(parameters: &[OptionParameter<B>]) -> impl quote::ToTokens {
+    let any_flattened = parameters.iter().any(OptionParameter::is_flattened);
+    let command_options = parameters.iter().map(|parameter| {
+        let ty = parameter.ty;
+        if parameter.is_flattened() {
+            return quote! { <#ty as ::tranquil::resolve::CommandOptions>::options() };
+        }
+
+        let name = &parameter.name;
+        let description = optional_description(parameter.attributes.description.as_deref());
+        let constraints = parameter.attributes.constraints();
+        let set_autocomplete = parameter
+            .attributes
+            .autocomplete
+            .is_some()
+            .then(|| quote! { option.set_autocomplete(true); });
+        let command_option = quote! {
+            ::tranquil::command::CommandOption {
+                name: ::std::convert::From::from(#name),
+                description: #description,
+                builder: (|l10n: &::tranquil::l10n::L10n| {
+                    let mut option = ::tranquil::serenity::builder::CreateApplicationCommandOption::default();
+                    <#ty as ::tranquil::resolve::Resolve>::describe(
+                        option
+                            .kind(<#ty as ::tranquil::resolve::Resolve>::KIND)
+                            .required(<#ty as ::tranquil::resolve::Resolve>::REQUIRED),
+                        l10n,
+                    );
+                    #constraints.describe(<#ty as ::tranquil::resolve::Resolve>::KIND, &mut option);
+                    #set_autocomplete
+                    option
+                }) as ::tranquil::command::OptionBuilder,
+            }
+        };
+
+        if any_flattened {
+            quote! { ::std::vec![#command_option] }
+        } else {
+            command_option
+        }
+    });
+
+    if any_flattened {
+        quote! {
+            ::std::iter::Iterator::collect(::std::iter::Iterator::flatten(
+                ::std::iter::IntoIterator::into_iter([#(#command_options),*]),
+            ))
+        }
+    } else {
+        quote! { ::std::vec![#(#command_options),*] }
+    }
+}
+
+/// Resolves all parameters from `options` and binds them, returning [`InvalidParameters`] early.
+///
+/// Resolves all of them instead of stopping at the first error, so that every invalid parameter
+/// can be reported at once.
+fn resolve_parameters<B: quote::ToTokens>(
+    parameters: &[OptionParameter<B>],
+) -> impl quote::ToTokens {
+    if parameters.is_empty() {
+        return quote! {};
+    }
+
+    let resolvers = parameters.iter().map(|parameter| {
+        let ty = parameter.ty;
+        if parameter.is_flattened() {
+            quote! {
+                <#ty as ::tranquil::resolve::CommandOptions>::resolve(
+                    http.clone(),
+                    options
+                        .by_ref()
+                        .take(<#ty as ::tranquil::resolve::CommandOptions>::option_names().len())
+                        .collect(),
+                )
+            }
+        } else {
+            let constraints = parameter.attributes.constraints();
+            quote! {
+                #constraints.resolve::<#ty>(
+                    ::tranquil::resolve::ResolveContext {
+                        // Technically unwrap instead of flatten would also work, but better safe than sorry.
+                        option: options.next().flatten(),
+                        http: http.clone(),
+                    },
+                )
+            }
+        }
+    });
+
+    let resolved = (0..parameters.len())
+        .map(|index| format_ident!("__parameter_{index}"))
+        .collect::<Vec<_>>();
+
+    let checks = parameters
+        .iter()
+        .zip(&resolved)
+        .map(|(parameter, resolved)| {
+            if parameter.is_flattened() {
+                quote! { invalid_parameters.check_options(#resolved) }
+            } else {
+                let name = &parameter.name;
+                quote! { invalid_parameters.check(#name, #resolved) }
+            }
+        });
+
+    let bindings = parameters.iter().map(|parameter| parameter.binding);
+
+    quote! {
+        let (#(#resolved),*,) = ::tranquil::serenity::futures::join!(#(#resolvers),*);
+        let mut invalid_parameters = ::tranquil::resolve::InvalidParameters::default();
+        let (#(::std::option::Option::Some(#bindings)),*,) = (#(#checks),*,) else {
+            return ::std::result::Result::Err(invalid_parameters);
+        };
+    }
+}
+
+fn optional_description(description: Option<&str>) -> impl quote::ToTokens {
+    match description {
+        Some(description) => quote! { ::std::option::Option::Some(#description) },
+        None => quote! { ::std::option::Option::None },
+    }
+}
+
+fn per_without_cooldown(span: &impl Spanned) -> TokenStream {
+    syn::Error::new(span.span(), "`per` requires a `cooldown`")
+        .into_compile_error()
+        .into()
+}
+
+fn parse_permissions(value: &Expr) -> Result<Vec<Ident>, TokenStream> {
+    let invalid_permissions = || {
+        TokenStream::from(
+            syn::Error::new(
+                value.span(),
+                r#"expected permissions, e.g. "MANAGE_GUILD | BAN_MEMBERS""#,
+            )
+            .into_compile_error(),
+        )
+    };
+
+    match value {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit_str),
+            ..
+        }) => {
+            let permissions = lit_str.value();
+            if permissions.trim().is_empty() {
+                return Ok(vec![]);
+            }
+            permissions
+                .split('|')
+                .map(|permission| {
+                    syn::parse_str::<Ident>(permission.trim())
+                        .map(|ident| Ident::new(&ident.to_string(), lit_str.span()))
+                        .map_err(|_| invalid_permissions())
+                })
+                .collect()
+        }
+        _ => Err(invalid_permissions()),
+    }
+}
+
+/// Parses durations like `"1m 30s"` into milliseconds.
+fn parse_duration(value: &Expr) -> Result<u64, TokenStream> {
+    let invalid_duration = || {
+        TokenStream::from(
+            syn::Error::new(
+                value.span(),
+                r#"expected duration, e.g. "10s", "500ms" or "1h 30m""#,
+            )
+            .into_compile_error(),
+        )
+    };
+
+    let Expr::Lit(ExprLit {
+        lit: Lit::Str(lit_str),
+        ..
+    }) = value
+    else {
+        return Err(invalid_duration());
+    };
+
+    let duration = lit_str.value().replace(' ', "");
+    let mut rest = duration.as_str();
+    let mut millis = 0u64;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (number, unit_and_rest) = rest.split_at(digits);
+        let number = number.parse::<u64>().map_err(|_| invalid_duration())?;
+        let unit = unit_and_rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(unit_and_rest.len());
+        let (unit, next) = unit_and_rest.split_at(unit);
+        let factor = match unit {
+            "ms" => 1,
+            "s" => 1000,
+            "m" => 60 * 1000,
+            "h" => 60 * 60 * 1000,
+            "d" => 24 * 60 * 60 * 1000,
+            _ => return Err(invalid_duration()),
+        };
+        millis = number
+            .checked_mul(factor)
+            .and_then(|duration| millis.checked_add(duration))
+            .ok_or_else(invalid_duration)?;
+        rest = next;
+    }
+
+    if millis == 0 {
+        Err(invalid_duration())
+    } else {
+        Ok(millis)
+    }
+}
+
+fn parse_cooldown_scope(value: &Expr) -> Result<Ident, TokenStream> {
+    let scope = match value {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit_str),
+            ..
+        }) => match lit_str.value().as_str() {
+            "user" => Some("User"),
+            "guild" => Some("Guild"),
+            "channel" => Some("Channel"),
+            "global" => Some("Global"),
+            _ => None,
+        },
+        _ => None,
+    };
+    scope
+        .map(|scope| Ident::new(scope, value.span()))
+        .ok_or_else(|| {
+            syn::Error::new(
+                value.span(),
+                r#"expected "user", "guild", "channel" or "global""#,
+            )
+            .into_compile_error()
+            .into()
+        })
+}
+
+fn parse_scope(value: &Expr) -> Result<Scope, TokenStream> {
+    let invalid_scope = || {
+        TokenStream::from(
+            syn::Error::new(
+                value.span(),
+                r#"expected "global", "connected" or a list of guild ids, e.g. [1234, 5678]"#,
+            )
+            .into_compile_error(),
+        )
+    };
+
+    match value {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit_str),
+            ..
+        }) => match lit_str.value().as_str() {
+            "global" => Ok(Scope::Global),
+            "connected" => Ok(Scope::Connected),
+            _ => Err(invalid_scope()),
+        },
+        Expr::Array(ExprArray { elems, .. }) => elems
+            .iter()
+            .map(|elem| match elem {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Int(lit_int),
+                    ..
+                }) => lit_int.base10_parse().map_err(|_| invalid_scope()),
+                _ => Err(invalid_scope()),
+            })
+            .collect::<Result<_, _>>()
+            .map(Scope::Only),
+        _ => Err(invalid_scope()),
+    }
+}
+
+fn parse_auto_defer(value: &Expr) -> Result<AutoDefer, TokenStream> {
+    match value {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit_str),
+            ..
+        }) if lit_str.value() == "ephemeral" => Ok(AutoDefer::Ephemeral),
+        _ => Err(syn::Error::new(value.span(), r#"expected "ephemeral""#)
+            .into_compile_error()
+            .into()),
+    }
+}
+
+fn parse_dm(value: &Expr) -> Result<bool, TokenStream> {
+    match value {
+        Expr::Lit(ExprLit {
+            lit: Lit::Bool(lit_bool),
+            ..
+        }) => Ok(lit_bool.value),
+        _ => Err(syn::Error::new(value.span(), "expected `true` or `false`")
+            .into_compile_error()
+            .into()),
+    }
+}
+
+fn command_permissions(permissions: Option<&[Ident]>, dm: Option<bool>) -> impl quote::ToTokens {
+    let default_member_permissions = match permissions {
+        Some(permissions) => quote! {
+            ::std::option::Option::Some(
+                ::tranquil::serenity::model::permissions::Permissions::empty()
+                    #(| ::tranquil::serenity::model::permissions::Permissions::#permissions)*
+            )
+        },
+        None => quote! { ::std::option::Option::None },
+    };
+    let dm_permission = match dm {
+        Some(dm) => quote! { ::std::option::Option::Some(#dm) },
+        None => quote! { ::std::option::Option::None },
+    };
+    quote! {
+        ::tranquil::command::CommandPermissions {
+            default_member_permissions: #default_member_permissions,
+            dm_permission: #dm_permission,
+        }
+    }
+}
+
+fn command_scope(scope: Option<&Scope>) -> impl quote::ToTokens {
+    let scope = scope.map(|scope| match scope {
+        Scope::Global => quote! { ::tranquil::bot::ApplicationCommandUpdate::Global },
+        Scope::Connected => quote! { ::tranquil::bot::ApplicationCommandUpdate::Connected },
+        Scope::Only(guilds) => quote! {
+            ::tranquil::bot::ApplicationCommandUpdate::Only(::std::vec![
+                #(::tranquil::serenity::model::id::GuildId(#guilds)),*
+            ])
+        },
+    });
+    match scope {
+        Some(scope) => quote! { ::std::option::Option::Some(#scope) },
+        None => quote! { ::std::option::Option::None },
+    }
+}
+
+fn command_auto_defer(auto_defer: Option<AutoDefer>) -> impl quote::ToTokens {
+    match auto_defer {
+        Some(AutoDefer::Public) => quote! {
+            ::std::option::Option::Some(::tranquil::auto_defer::AutoDefer::new())
+        },
+        Some(AutoDefer::Ephemeral) => quote! {
+            ::std::option::Option::Some(::tranquil::auto_defer::AutoDefer::ephemeral())
+        },
+        None => quote! { ::std::option::Option::None },
+    }
+}
+
+fn command_cooldown(
+    cooldown: Option<u64>,
+    per: Option<Ident>,
+    errors: &mut Vec<TokenStream>,
+) -> impl quote::ToTokens {
+    match (cooldown, per) {
+        (Some(millis), per) => {
+            let per = per.unwrap_or_else(|| format_ident!("User"));
+            quote! {
+                ::std::option::Option::Some(::tranquil::cooldown::Cooldown::new(
+                    ::std::time::Duration::from_millis(#millis),
+                    ::tranquil::cooldown::CooldownScope::#per,
+                ))
+            }
+        }
+        (None, per) => {
+            if let Some(per) = per {
+                errors.push(per_without_cooldown(&per));
+            }
+            quote! { ::std::option::Option::None }
+        }
+    }
+}
+
 #[proc_macro_attribute]
 pub fn slash(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let mut errors = vec![];
-
-    let nested_metas =
-        parse_macro_input!(attr with Punctuated::<Meta, Token![,]>::parse_terminated);
+    let mut attributes = parse_macro_input!(attr as CommandAttributes);
+    let mut errors = std::mem::take(&mut attributes.errors);
+    errors.extend(attributes.unknown.iter().map(invalid_attribute));
 
     let mut item_fn = parse_macro_input!(item as ItemFn);
-    let name = item_fn.sig.ident;
-    let impl_name = format_ident!("__{name}");
-    item_fn.sig.ident = impl_name.clone();
+    let (name, impl_name) = rename_handler(&mut item_fn);
 
-    let attributes = {
-        let mut attributes = SlashAttributes::default();
-        for nested_meta in &nested_metas {
-            match nested_meta {
-                Meta::NameValue(MetaNameValue { path, value, .. }) => {
-                    let ident = path.get_ident();
-                    if ident.is_some_and(|ident| ident == "rename") {
-                        match value {
-                            Expr::Lit(ExprLit {
-                                lit: Lit::Str(lit_str),
-                                ..
-                            }) => {
-                                if attributes.rename.is_some() {
-                                    errors.push(multiple_renames(&nested_meta));
-                                } else {
-                                    match parse_command(lit_str, ' ') {
-                                        Ok(command) => attributes.rename = Some(command),
-                                        Err(error) => errors.push(error),
-                                    }
-                                }
-                            }
-                            _ => errors.push(invalid_rename_literal(&value)),
-                        }
-                    } else if ident.is_some_and(|ident| ident == "autocomplete") {
-                        match value {
-                            Expr::Lit(ExprLit {
-                                lit: Lit::Str(lit_str),
-                                ..
-                            }) => {
-                                if attributes.autocomplete.is_some() {
-                                    errors.push(multiple_autocompletes(&nested_meta));
-                                } else {
-                                    match lit_str.parse_with(syn::Ident::parse) {
-                                        Ok(ident) => {
-                                            attributes.autocomplete =
-                                                Some(Autocomplete::CustomName(ident))
-                                        }
-                                        Err(_) => errors.push(invalid_autocomplete_ident(&value)),
-                                    }
-                                }
+    let command_path = match &attributes.rename {
+        Some(rename) => parse_command(rename, ' '),
+        None => parse_command(&name, '_'),
+    }
+    .unwrap_or_else(|error| {
+        errors.push(error);
+        CommandPath::Command {
+            name: name.to_string(),
+        }
+    });
+
+    if let (Some(ident), CommandPath::Command { .. }) = (&attributes.default, &command_path) {
+        errors.push(default_on_base_command(ident));
+    }
+
+    let description = doc_description(&item_fn.attrs);
+
+    let parameter_attributes = item_fn
+        .sig
+        .inputs
+        .iter_mut()
+        .skip(2)
+        .filter_map(|input| match input {
+            FnArg::Receiver(_) => None,
+            FnArg::Typed(pat_type) => Some(pat_type),
+        })
+        .map(|pat_type| take_parameter_attributes(&mut pat_type.attrs, &mut errors))
+        .collect::<Vec<_>>();
+
+    let option_parameters = item_fn
+        .sig
+        .inputs
+        .iter()
+        .skip(2) // TODO: Don't just skip &self and CommandCtx.
+        .filter_map(|input| match input {
+            FnArg::Receiver(_) => None,
+            FnArg::Typed(pat_type) => Some(pat_type),
+        })
+        .zip(parameter_attributes)
+        .map(|(PatType { pat, ty, .. }, attributes)| OptionParameter {
+            binding: pat,
+            ty,
+            name: match &attributes.rename {
+                Some(rename) => rename.clone(),
+                None => LitStr::new(&pat.to_token_stream().to_string(), pat.span()),
+            },
+            attributes,
+        })
+        .collect::<Vec<_>>();
+
+    let parameters = option_parameters
+        .iter()
+        .map(|parameter| parameter.binding)
+        .collect::<Vec<_>>();
+    let option_names = option_names(&option_parameters);
+    let resolve_parameters = resolve_parameters(&option_parameters);
+
+    let autocompleter_name = match &attributes.autocomplete {
+        Some(Autocomplete::DefaultName(_)) => Some(format_ident!("autocomplete_{name}")),
+        Some(Autocomplete::CustomName(name)) => Some(format_ident!("{name}")),
+        None => None,
+    };
+
+    let (autocompleted_options, option_autocompleters): (Vec<_>, Vec<_>) = option_parameters
+        .iter()
+        .filter_map(|parameter| {
+            Some((&parameter.name, parameter.attributes.autocomplete.as_ref()?))
+        })
+        .unzip();
+
+    // Autocompleters of individual options take precedence over the one of the whole command.
+    let autocompleter = match (&autocompleter_name, option_autocompleters.is_empty()) {
+        (None, true) => quote! { ::std::option::Option::None },
+        (Some(autocompleter_name), true) => quote! {
+            ::std::option::Option::Some(
+                ::std::boxed::Box::new(|module, ctx, options| {
+                    ::std::boxed::Box::pin(async move {
+                        module.#autocompleter_name(ctx, options).await
+                    })
+                })
+            )
+        },
+        (autocompleter_name, false) => {
+            let fallback = match autocompleter_name {
+                Some(autocompleter_name) => quote! {
+                    module.#autocompleter_name(ctx, options).await
+                },
+                None => quote! {
+                    ::std::result::Result::Err(::tranquil::anyhow::anyhow!("no autocomplete handler"))
+                },
+            };
+            quote! {
+                ::std::option::Option::Some(
+                    ::std::boxed::Box::new(|module, ctx, options| {
+                        ::std::boxed::Box::pin(async move {
+                            let focused = ::tranquil::resolve::focused_option(&options)
+                                .map(::std::borrow::ToOwned::to_owned);
+                            match focused.as_deref() {
+                                #(::std::option::Option::Some(#autocompleted_options) => {
+                                    module.#option_autocompleters(ctx, options).await
+                                })*
+                                _ => #fallback,
                             }
-                            _ => errors.push(invalid_autocomplete_ident(&value)),
-                        }
-                    } else {
-                        errors.push(invalid_attribute(&nested_meta));
-                    }
-                }
-                Meta::Path(path) => {
-                    let ident = path.get_ident();
-                    if ident.is_some_and(|ident| ident == "default") {
-                        attributes.default = ident;
-                    } else if ident.is_some_and(|ident| ident == "autocomplete") {
-                        attributes.autocomplete = Some(Autocomplete::DefaultName);
-                    } else {
-                        errors.push(invalid_attribute(&nested_meta));
-                    }
-                }
-                _ => {
-                    errors.push(invalid_attribute(&nested_meta));
+                        })
+                    })
+                )
+            }
+        }
+    };
+
+    let (root_name, is_top_level) = match &command_path {
+        CommandPath::Command { name } => (name.clone(), true),
+        CommandPath::Subcommand { name, .. } | CommandPath::Grouped { name, .. } => {
+            (name.clone(), false)
+        }
+    };
+
+    let command_path = match &command_path {
+        CommandPath::Command { name } => {
+            quote! {
+                ::tranquil::command::CommandPath::Command {
+                    name: #name.to_string()
                 }
             }
         }
-        attributes
+        CommandPath::Subcommand { name, subcommand } => quote! {
+            ::tranquil::command::CommandPath::Subcommand {
+                name: #name.to_string(),
+                subcommand: #subcommand.to_string(),
+            }
+        },
+        CommandPath::Grouped {
+            name,
+            group,
+            subcommand,
+        } => quote! {
+            ::tranquil::command::CommandPath::Grouped {
+                name: #name.to_string(),
+                group: #group.to_string(),
+                subcommand: #subcommand.to_string(),
+            }
+        },
     };
 
-    let command_path = attributes
-        .rename
-        .map_or_else(|| parse_command(&name, '_'), Ok)
-        .unwrap_or_else(|error| {
-            errors.push(error);
-            CommandPath::Command {
-                name: name.to_string(),
+    let command_options = command_options(&option_parameters);
+
+    let is_default_option = attributes.default.is_some();
+    let (permissions, cooldown, scope, auto_defer) = attributes.settings(&mut errors);
+    let description = optional_description(description.as_deref());
+
+    if let Some(subcommands) = option_parameters
+        .iter()
+        .find(|parameter| parameter.attributes.subcommands.is_some())
+    {
+        let subcommands_attr = subcommands.attributes.subcommands.as_ref().unwrap();
+        if option_parameters.len() != 1 {
+            errors.push(subcommands_with_other_parameters(subcommands_attr));
+        }
+        if !is_top_level {
+            errors.push(subcommands_on_subcommand(subcommands_attr));
+        }
+
+        let binding = subcommands.binding;
+        let ty = subcommands.ty;
+
+        let mut result = TokenStream::from(quote! {
+            #item_fn
+
+            fn #name(
+                self: ::std::sync::Arc<Self>,
+            ) -> ::std::vec::Vec<(
+                ::tranquil::command::CommandPath,
+                ::std::boxed::Box<dyn ::tranquil::command::Command>,
+            )> {
+                <#ty as ::tranquil::resolve::Subcommands>::subcommands()
+                    .into_iter()
+                    .map(|subcommand| {
+                        let path = match subcommand.group {
+                            ::std::option::Option::Some(group) => ::tranquil::command::CommandPath::Grouped {
+                                name: #root_name.to_string(),
+                                group: group.to_string(),
+                                subcommand: subcommand.name.to_string(),
+                            },
+                            ::std::option::Option::None => ::tranquil::command::CommandPath::Subcommand {
+                                name: #root_name.to_string(),
+                                subcommand: subcommand.name.to_string(),
+                            },
+                        };
+                        let resolver = subcommand.resolver;
+                        (
+                            path,
+                            ::std::boxed::Box::new(::tranquil::command::ModuleCommand::new(
+                                self.clone(),
+                                ::std::boxed::Box::new(move |module, http, options| {
+                                    let subcommand = resolver(
+                                        http,
+                                        ::tranquil::resolve::resolve_command_options(options),
+                                    );
+                                    ::std::boxed::Box::pin(async move {
+                                        let #binding = subcommand.await?;
+                                        ::std::result::Result::Ok(::std::boxed::Box::new(move |ctx| {
+                                            ::std::boxed::Box::pin(async move {
+                                                module.#impl_name(ctx, #binding).await
+                                            }) as ::std::pin::Pin<::std::boxed::Box<dyn ::std::future::Future<Output = _> + ::std::marker::Send>>
+                                        }) as ::tranquil::command::CommandHandler)
+                                    })
+                                }),
+                                #autocompleter,
+                                ::tranquil::command::ModuleCommandSettings {
+                                    description: subcommand.description,
+                                    group_description: subcommand.group_description,
+                                    root_description: #description,
+                                    options: subcommand.options,
+                                    default_option: false,
+                                    permissions: #permissions,
+                                    cooldown: #cooldown,
+                                    scope: #scope,
+                                    auto_defer: #auto_defer,
+                                },
+                            )) as ::std::boxed::Box<dyn ::tranquil::command::Command>,
+                        )
+                    })
+                    .collect()
             }
         });
+        result.extend(errors);
+        return result;
+    }
 
-    if let (Some(ident), CommandPath::Command { .. }) = (attributes.default, &command_path) {
-        errors.push(default_on_base_command(ident));
+    let mut result = TokenStream::from(quote! {
+        #item_fn
+
+        fn #name(
+            self: ::std::sync::Arc<Self>,
+        ) -> ::std::vec::Vec<(
+            ::tranquil::command::CommandPath,
+            ::std::boxed::Box<dyn ::tranquil::command::Command>,
+        )> {
+            ::std::vec![(
+                #command_path,
+                ::std::boxed::Box::new(::tranquil::command::ModuleCommand::new(
+                    self,
+                    ::std::boxed::Box::new(|module, http, options| {
+                        ::std::boxed::Box::pin(async move {
+                            let mut options = ::tranquil::resolve::find_options(
+                                #option_names,
+                                ::tranquil::resolve::resolve_command_options(options),
+                            );
+                            #resolve_parameters
+                            ::std::result::Result::Ok(::std::boxed::Box::new(move |ctx| {
+                                ::std::boxed::Box::pin(async move {
+                                    module.#impl_name(ctx, #(#parameters),*).await
+                                }) as ::std::pin::Pin<::std::boxed::Box<dyn ::std::future::Future<Output = _> + ::std::marker::Send>>
+                            }) as ::tranquil::command::CommandHandler)
+                        })
+                    }),
+                    #autocompleter,
+                    ::tranquil::command::ModuleCommandSettings {
+                        description: #description,
+                        options: #command_options,
+                        default_option: #is_default_option,
+                        permissions: #permissions,
+                        cooldown: #cooldown,
+                        scope: #scope,
+                        auto_defer: #auto_defer,
+                        ..::std::default::Default::default()
+                    },
+                )),
+            )]
+        }
+    });
+    result.extend(errors);
+    result
+}
+
+fn subcommands_with_other_parameters(attr: &Attribute) -> TokenStream {
+    syn::Error::new(
+        attr.span(),
+        "`subcommands` must be the only parameter after the CommandCtx",
+    )
+    .into_compile_error()
+    .into()
+}
+
+fn subcommands_on_subcommand(attr: &Attribute) -> TokenStream {
+    syn::Error::new(
+        attr.span(),
+        "`subcommands` can only be used on top-level commands",
+    )
+    .into_compile_error()
+    .into()
+}
+
+fn invalid_context_menu_attribute(span: &impl Spanned) -> TokenStream {
+    syn::Error::new(
+        span.span(),
+        indoc! {r#"
+            available attributes are
+                `rename = "..."`
+                `permissions = "..."`
+                `dm = true|false`
+                `cooldown = "..."`
+                `per = "user|guild|channel|global"`
+                `scope = "global|connected"`
+                `scope = [guild_id, ...]`
+                `auto_defer`
+                `auto_defer = "ephemeral"`
+        "#},
+    )
+    .into_compile_error()
+    .into()
+}
+
+fn invalid_context_menu_parameters(span: &impl Spanned, target: &str) -> TokenStream {
+    syn::Error::new(
+        span.span(),
+        format!("expected exactly one parameter for the target {target} after the CommandCtx"),
+    )
+    .into_compile_error()
+    .into()
+}
+
+#[derive(Clone, Copy)]
+enum ContextMenuKind {
+    User,
+    Message,
+}
+
+#[proc_macro_attribute]
+pub fn user_command(attr: TokenStream, item: TokenStream) -> TokenStream {
+    context_menu_command(ContextMenuKind::User, attr, item)
+}
+
+#[proc_macro_attribute]
+pub fn message_command(attr: TokenStream, item: TokenStream) -> TokenStream {
+    context_menu_command(ContextMenuKind::Message, attr, item)
+}
+
+fn context_menu_command(
+    kind: ContextMenuKind,
+    attr: TokenStream,
+    item: TokenStream,
+) -> TokenStream {
+    let mut attributes = parse_macro_input!(attr as CommandAttributes);
+    let mut errors = std::mem::take(&mut attributes.errors);
+    errors.extend(
+        attributes
+            .unknown
+            .iter()
+            .map(invalid_context_menu_attribute),
+    );
+    if let Some(default) = &attributes.default {
+        errors.push(invalid_context_menu_attribute(default));
     }
+    if let Some(Autocomplete::DefaultName(ident) | Autocomplete::CustomName(ident)) =
+        &attributes.autocomplete
+    {
+        errors.push(invalid_context_menu_attribute(ident));
+    }
+
+    let mut item_fn = parse_macro_input!(item as ItemFn);
+    let (name, impl_name) = rename_handler(&mut item_fn);
+
+    let (variant, resolve_target, target_name) = match kind {
+        ContextMenuKind::User => (
+            format_ident!("User"),
+            format_ident!("resolve_user_target"),
+            "user",
+        ),
+        ContextMenuKind::Message => (
+            format_ident!("Message"),
+            format_ident!("resolve_message_target"),
+            "message",
+        ),
+    };
 
     let typed_parameters = item_fn
         .sig
@@ -257,149 +1431,63 @@ pub fn slash(attr: TokenStream, item: TokenStream) -> TokenStream {
         .filter_map(|input| match input {
             FnArg::Receiver(_) => None,
             FnArg::Typed(pat_type) => Some(pat_type),
-        });
-
-    let parameters = typed_parameters
-        .clone()
-        .map(|PatType { pat, .. }| pat)
+        })
         .collect::<Vec<_>>();
 
-    let parameter_names = parameters
-        .iter()
-        .map(|parameter| quote! { ::std::stringify!(#parameter) });
-
-    let parameter_resolvers = typed_parameters.clone().map(|PatType { ty, .. }| {
-        quote! {
-            <#ty as ::tranquil::resolve::Resolve>::resolve(
-                ::tranquil::resolve::ResolveContext {
-                    // Technically unwrap instead of flatten would also work, but better safe than sorry.
-                    option: options.next().flatten(),
-                    http: ctx.bot.http.clone(),
-                },
-            )
-        }
-    });
-
-    let join_futures = if parameters.is_empty() {
-        quote! {}
-    } else {
-        quote! {
-            let (#(#parameters),*,) = ::tranquil::serenity::futures::try_join!(#(#parameter_resolvers),*)?;
-        }
-    };
-
-    let autocompleter = if let Some(autocomplete) = attributes.autocomplete {
-        let autocompleter_name = match autocomplete {
-            Autocomplete::DefaultName => format_ident!("autocomplete_{name}"),
-            Autocomplete::CustomName(name) => format_ident!("{name}"),
-        };
-        quote! {
-            ::std::option::Option::Some(
-                ::std::boxed::Box::new(|module, ctx, options| {
-                    ::std::boxed::Box::pin(async move {
-                        module.#autocompleter_name(ctx, options).await
-                    })
-                })
-            )
-        }
-    } else {
-        quote! { ::std::option::Option::None }
-    };
-
-    let make_command_path = |reference| {
-        let command_path_or_ref = if reference {
-            quote! { l10n::CommandPathRef }
-        } else {
-            quote! { command::CommandPath }
-        };
-
-        let to_string = if reference {
-            quote! {}
-        } else {
-            quote! { .to_string() }
-        };
-
-        match &command_path {
-            CommandPath::Command { name } => {
-                quote! {
-                    ::tranquil::#command_path_or_ref::Command {
-                        name: #name #to_string
-                    }
-                }
-            }
-            CommandPath::Subcommand { name, subcommand } => quote! {
-                ::tranquil::#command_path_or_ref::Subcommand {
-                    name: #name #to_string,
-                    subcommand: #subcommand #to_string,
-                }
-            },
-            CommandPath::Grouped {
-                name,
-                group,
-                subcommand,
-            } => quote! {
-                ::tranquil::#command_path_or_ref::Grouped {
-                    name: #name #to_string,
-                    group: #group #to_string,
-                    subcommand: #subcommand #to_string,
-                }
-            },
+    let target_type = match typed_parameters.as_slice() {
+        [PatType { ty, .. }] => quote! { #ty },
+        _ => {
+            errors.push(invalid_context_menu_parameters(
+                &item_fn.sig.inputs,
+                target_name,
+            ));
+            quote! { _ }
         }
     };
 
-    let command_path = make_command_path(false);
-    let command_path_ref = make_command_path(true);
-
-    let command_options = typed_parameters.map(|PatType { pat, ty, .. }| {
-        quote! {
-            (
-                ::std::convert::From::from(::std::stringify!(#pat)),
-                (|l10n: &::tranquil::l10n::L10n| {
-                    let mut option = ::tranquil::serenity::builder::CreateApplicationCommandOption::default();
-                    <#ty as ::tranquil::resolve::Resolve>::describe(
-                        option
-                            .kind(<#ty as ::tranquil::resolve::Resolve>::KIND)
-                            .required(<#ty as ::tranquil::resolve::Resolve>::REQUIRED),
-                        l10n,
-                    );
-                    // TODO: This can technically be done outside of the macro, now that the name is accessible there.
-                    l10n.describe_command_option(#command_path_ref, ::std::stringify!(#pat), &mut option);
-                    option
-                }) as fn(&::tranquil::l10n::L10n) -> ::tranquil::serenity::builder::CreateApplicationCommandOption,
-            )
-        }
-    });
-
-    let is_default_option = attributes.default.is_some();
+    let command_name = attributes
+        .rename
+        .as_ref()
+        .map_or_else(|| name.to_string(), LitStr::value);
+    let (permissions, cooldown, scope, auto_defer) = attributes.settings(&mut errors);
 
     let mut result = TokenStream::from(quote! {
         #item_fn
 
         fn #name(
             self: ::std::sync::Arc<Self>,
-        ) -> (
+        ) -> ::std::vec::Vec<(
             ::tranquil::command::CommandPath,
             ::std::boxed::Box<dyn ::tranquil::command::Command>,
-        ) {
-            (
-                #command_path,
+        )> {
+            ::std::vec![(
+                ::tranquil::command::CommandPath::#variant {
+                    name: ::std::convert::From::from(#command_name),
+                },
                 ::std::boxed::Box::new(::tranquil::command::ModuleCommand::new(
                     self,
-                    ::std::boxed::Box::new(|module, ctx, options| {
+                    ::std::boxed::Box::new(|module, _http, _options| {
                         ::std::boxed::Box::pin(async move {
-                            let mut options = ::tranquil::resolve::find_options(
-                                [#(#parameter_names),*],
-                                ::tranquil::resolve::resolve_command_options(options),
-                            );
-                            #join_futures
-                            module.#impl_name(ctx, #(#parameters),*).await
+                            ::std::result::Result::Ok(::std::boxed::Box::new(move |ctx: ::tranquil::context::command::CommandCtx| {
+                                ::std::boxed::Box::pin(async move {
+                                    let target = ::tranquil::context_menu::#resolve_target::<#target_type>(
+                                        &ctx.interaction.data,
+                                    )?;
+                                    module.#impl_name(ctx, target).await
+                                }) as ::std::pin::Pin<::std::boxed::Box<dyn ::std::future::Future<Output = _> + ::std::marker::Send>>
+                            }) as ::tranquil::command::CommandHandler)
                         })
                     }),
-                    #autocompleter,
-                    ::std::vec![#(#command_options),*],
-                    #is_default_option,
+                    ::std::option::Option::None,
+                    ::tranquil::command::ModuleCommandSettings {
+                        permissions: #permissions,
+                        cooldown: #cooldown,
+                        scope: #scope,
+                        auto_defer: #auto_defer,
+                        ..::std::default::Default::default()
+                    },
                 )),
-            )
+            )]
         }
     });
     result.extend(errors);
@@ -427,6 +1515,19 @@ pub fn autocompleter(attr: TokenStream, item: TokenStream) -> TokenStream {
     let impl_name = format_ident!("__{name}");
     item_fn.sig.ident = impl_name.clone();
 
+    // Only `#[rename]` matters here, so that renamed options can be autocompleted as well.
+    let renames = item_fn
+        .sig
+        .inputs
+        .iter_mut()
+        .skip(2)
+        .filter_map(|input| match input {
+            FnArg::Receiver(_) => None,
+            FnArg::Typed(pat_type) => Some(pat_type),
+        })
+        .map(|pat_type| take_parameter_attributes(&mut pat_type.attrs, &mut errors).rename)
+        .collect::<Vec<_>>();
+
     let typed_parameters = item_fn
         .sig
         .inputs
@@ -444,7 +1545,11 @@ pub fn autocompleter(attr: TokenStream, item: TokenStream) -> TokenStream {
 
     let parameter_names = parameters
         .iter()
-        .map(|parameter| quote! { ::std::stringify!(#parameter) });
+        .zip(renames)
+        .map(|(parameter, rename)| match rename {
+            Some(rename) => quote! { #rename },
+            None => quote! { ::std::stringify!(#parameter) },
+        });
 
     let parameter_resolvers = typed_parameters.map(|PatType { ty, .. }| {
         quote! {
@@ -488,6 +1593,26 @@ pub fn autocompleter(attr: TokenStream, item: TokenStream) -> TokenStream {
     result
 }
 
+fn invalid_command_provider_attribute(span: &impl Spanned) -> TokenStream {
+    syn::Error::new(
+        span.span(),
+        indoc! {r#"
+            available attributes are
+                `middleware = "..."`
+                `permissions = "..."`
+                `dm = true|false`
+        "#},
+    )
+    .into_compile_error()
+    .into()
+}
+
+fn invalid_middleware_ident(span: &impl Spanned) -> TokenStream {
+    syn::Error::new(span.span(), "expected identifier")
+        .into_compile_error()
+        .into()
+}
+
 #[proc_macro_attribute]
 pub fn command_provider(attr: TokenStream, item: TokenStream) -> TokenStream {
     // TODO: Deduplicate code
@@ -497,24 +1622,111 @@ pub fn command_provider(attr: TokenStream, item: TokenStream) -> TokenStream {
 
     let mut errors = vec![];
 
-    if let Some(meta) = nested_metas.first() {
-        errors.push(TokenStream::from(
-            syn::Error::new(
-                meta.span(),
-                "command_provider does not support any parameters",
-            )
-            .to_compile_error(),
-        ))
+    let mut middlewares = vec![];
+    let mut permissions = None;
+    let mut dm = None;
+    for nested_meta in &nested_metas {
+        match nested_meta {
+            Meta::NameValue(MetaNameValue { path, value, .. })
+                if path.get_ident().is_some_and(|ident| ident == "permissions") =>
+            {
+                if permissions.is_some() {
+                    errors.push(multiple_permissions(&nested_meta));
+                } else {
+                    match parse_permissions(value) {
+                        Ok(value) => permissions = Some(value),
+                        Err(error) => errors.push(error),
+                    }
+                }
+            }
+            Meta::NameValue(MetaNameValue { path, value, .. })
+                if path.get_ident().is_some_and(|ident| ident == "dm") =>
+            {
+                if dm.is_some() {
+                    errors.push(multiple_dms(&nested_meta));
+                } else {
+                    match parse_dm(value) {
+                        Ok(value) => dm = Some(value),
+                        Err(error) => errors.push(error),
+                    }
+                }
+            }
+            Meta::NameValue(MetaNameValue { path, value, .. })
+                if path.get_ident().is_some_and(|ident| ident == "middleware") =>
+            {
+                match value {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(lit_str),
+                        ..
+                    }) => match lit_str.parse_with(syn::Ident::parse) {
+                        Ok(ident) => middlewares.push(ident),
+                        Err(_) => errors.push(invalid_middleware_ident(&value)),
+                    },
+                    _ => errors.push(invalid_middleware_ident(&value)),
+                }
+            }
+            _ => errors.push(invalid_command_provider_attribute(&nested_meta)),
+        }
     }
 
     let impl_item = parse_macro_input!(item as ItemImpl);
     let type_name = &impl_item.self_ty;
 
     let commands = impl_item.items.iter().filter_map(|item| match item {
-        ImplItem::Fn(impl_item_method) => Some(&impl_item_method.sig.ident),
+        ImplItem::Fn(impl_item_method) if !middlewares.contains(&impl_item_method.sig.ident) => {
+            Some(&impl_item_method.sig.ident)
+        }
         _ => None,
     });
 
+    // Each middleware wraps the ones that were specified after it.
+    let middleware = middlewares.split_last().map(|(last, middlewares)| {
+        let body = middlewares.iter().rev().fold(
+            quote! { self.#last(ctx, next).await },
+            |inner, middleware| {
+                quote! {
+                    self.#middleware(
+                        ctx,
+                        ::tranquil::middleware::Next::from_fn(move |ctx| {
+                            ::std::boxed::Box::pin(async move { #inner })
+                        }),
+                    )
+                    .await
+                }
+            },
+        );
+
+        quote! {
+            fn middleware<'life0, 'life1, 'async_trait>(
+                &'life0 self,
+                ctx: ::tranquil::middleware::InteractionCtx,
+                next: ::tranquil::middleware::Next<'life1>,
+            ) -> ::std::pin::Pin<
+                ::std::boxed::Box<
+                    dyn ::std::future::Future<Output = ::tranquil::anyhow::Result<()>>
+                        + ::std::marker::Send
+                        + 'async_trait
+                >
+            >
+            where
+                'life0: 'async_trait,
+                'life1: 'async_trait,
+                Self: 'async_trait,
+            {
+                ::std::boxed::Box::pin(async move { #body })
+            }
+        }
+    });
+
+    let permissions = (permissions.is_some() || dm.is_some()).then(|| {
+        let permissions = command_permissions(permissions.as_deref(), dm);
+        quote! {
+            fn permissions(&self) -> ::tranquil::command::CommandPermissions {
+                #permissions
+            }
+        }
+    });
+
     let mut result = TokenStream::from(quote! {
         #impl_item
 
@@ -522,10 +1734,14 @@ pub fn command_provider(attr: TokenStream, item: TokenStream) -> TokenStream {
             fn command_map(
                 self: ::std::sync::Arc<Self>,
             ) -> ::std::result::Result<::tranquil::command::CommandMap, ::tranquil::command::CommandMapMergeError> {
-                ::tranquil::command::CommandMap::new([
-                    #(Self::#commands(self.clone())),*
-                ])
+                ::tranquil::command::CommandMap::new(
+                    ::std::iter::empty()#(.chain(Self::#commands(self.clone())))*
+                )
             }
+
+            #permissions
+
+            #middleware
         }
     });
     result.extend(errors);
@@ -578,6 +1794,299 @@ pub fn derive_choices(item: TokenStream) -> TokenStream {
     .into()
 }
 
+fn command_options_without_named_fields(span: &impl Spanned) -> TokenStream {
+    syn::Error::new(
+        span.span(),
+        "`CommandOptions` can only be derived for structs with named fields",
+    )
+    .into_compile_error()
+    .into()
+}
+
+fn autocomplete_in_command_options(span: &impl Spanned) -> TokenStream {
+    syn::Error::new(
+        span.span(),
+        "options of `CommandOptions` can only be autocompleted by the autocompleter of the command",
+    )
+    .into_compile_error()
+    .into()
+}
+
+#[proc_macro_derive(
+    CommandOptions,
+    attributes(
+        description,
+        rename,
+        min,
+        max,
+        min_length,
+        max_length,
+        channel_types,
+        autocomplete,
+        flatten
+    )
+)]
+pub fn derive_command_options(item: TokenStream) -> TokenStream {
+    let mut errors = vec![];
+
+    let struct_item = parse_macro_input!(item as ItemStruct);
+    let name = &struct_item.ident;
+    let (impl_generics, ty_generics, where_clause) = struct_item.generics.split_for_impl();
+
+    let Fields::Named(fields) = &struct_item.fields else {
+        return command_options_without_named_fields(&struct_item);
+    };
+
+    let option_parameters = fields
+        .named
+        .iter()
+        .filter_map(|field| Some((field.ident.as_ref()?, field)))
+        .map(|(ident, field)| {
+            let attributes = take_parameter_attributes(&mut field.attrs.clone(), &mut errors);
+            if let Some(autocomplete) = &attributes.autocomplete {
+                errors.push(autocomplete_in_command_options(autocomplete));
+            }
+            OptionParameter {
+                binding: ident,
+                ty: &field.ty,
+                name: match &attributes.rename {
+                    Some(rename) => rename.clone(),
+                    None => LitStr::new(&ident.unraw().to_string(), ident.span()),
+                },
+                attributes,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let fields = option_parameters
+        .iter()
+        .map(|parameter| parameter.binding)
+        .collect::<Vec<_>>();
+    let option_names = option_names(&option_parameters);
+    let command_options = command_options(&option_parameters);
+    let resolve_parameters = resolve_parameters(&option_parameters);
+
+    // Avoids unused variable warnings for structs without any fields.
+    let (http, options, into_iter) = if fields.is_empty() {
+        (format_ident!("_http"), format_ident!("_options"), quote! {})
+    } else {
+        (
+            format_ident!("http"),
+            format_ident!("options"),
+            quote! { let mut options = ::std::iter::IntoIterator::into_iter(options); },
+        )
+    };
+
+    let mut result = TokenStream::from(quote! {
+        #[::tranquil::async_trait]
+        impl #impl_generics ::tranquil::resolve::CommandOptions for #name #ty_generics #where_clause {
+            fn option_names() -> ::std::vec::Vec<&'static ::std::primitive::str> {
+                #option_names
+            }
+
+            fn options() -> ::std::vec::Vec<::tranquil::command::CommandOption> {
+                #command_options
+            }
+
+            async fn resolve(
+                #http: ::std::sync::Arc<::tranquil::serenity::http::Http>,
+                #options: ::std::vec::Vec<
+                    ::std::option::Option<
+                        ::tranquil::serenity::model::application::interaction::application_command::CommandDataOption,
+                    >,
+                >,
+            ) -> ::std::result::Result<Self, ::tranquil::resolve::InvalidParameters> {
+                #into_iter
+                #resolve_parameters
+                ::std::result::Result::Ok(Self { #(#fields),* })
+            }
+        }
+    });
+    result.extend(errors);
+    result
+}
+
+fn invalid_subcommand_variant(span: &impl Spanned) -> TokenStream {
+    syn::Error::new(
+        span.span(),
+        "expected named fields for a subcommand or a single unnamed field for a subcommand group",
+    )
+    .into_compile_error()
+    .into()
+}
+
+fn invalid_subcommand_attribute(span: &impl Spanned) -> TokenStream {
+    syn::Error::new(
+        span.span(),
+        "subcommands only support `rename` and `description`",
+    )
+    .into_compile_error()
+    .into()
+}
+
+/// Converts an `UpperCamelCase` identifier into `kebab-case`.
+fn kebab_case(ident: &Ident) -> String {
+    let mut result = String::new();
+    for (index, c) in ident.unraw().to_string().chars().enumerate() {
+        if c.is_uppercase() {
+            if index != 0 {
+                result.push('-');
+            }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+#[proc_macro_derive(
+    Subcommands,
+    attributes(
+        description,
+        rename,
+        min,
+        max,
+        min_length,
+        max_length,
+        channel_types,
+        autocomplete,
+        flatten
+    )
+)]
+pub fn derive_subcommands(item: TokenStream) -> TokenStream {
+    let mut errors = vec![];
+
+    let enum_item = parse_macro_input!(item as ItemEnum);
+    let name = &enum_item.ident;
+    let (impl_generics, ty_generics, where_clause) = enum_item.generics.split_for_impl();
+
+    let mut has_groups = false;
+    let subcommands = enum_item
+        .variants
+        .iter()
+        .map(|variant| {
+            let attributes = take_parameter_attributes(&mut variant.attrs.clone(), &mut errors);
+            let has_invalid_attributes = attributes.min.is_some()
+                || attributes.max.is_some()
+                || attributes.min_length.is_some()
+                || attributes.max_length.is_some()
+                || attributes.channel_types.is_some()
+                || attributes.autocomplete.is_some()
+                || attributes.flatten.is_some();
+            if has_invalid_attributes {
+                errors.push(invalid_subcommand_attribute(variant));
+            }
+
+            let ident = &variant.ident;
+            let subcommand_name = attributes
+                .rename
+                .unwrap_or_else(|| LitStr::new(&kebab_case(ident), ident.span()));
+
+            let named_fields = match &variant.fields {
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                    has_groups = true;
+                    let description = optional_description(attributes.description.as_deref());
+                    return quote! {
+                        subcommands.extend(::tranquil::resolve::subcommand_group(
+                            #subcommand_name,
+                            #description,
+                            Self::#ident,
+                        ));
+                    };
+                }
+                Fields::Named(fields) => fields.named.iter().collect::<Vec<_>>(),
+                Fields::Unit => vec![],
+                Fields::Unnamed(_) => {
+                    errors.push(invalid_subcommand_variant(variant));
+                    return quote! {};
+                }
+            };
+
+            let option_parameters = named_fields
+                .into_iter()
+                .filter_map(|field| Some((field.ident.as_ref()?, field)))
+                .map(|(ident, field)| {
+                    let attributes =
+                        take_parameter_attributes(&mut field.attrs.clone(), &mut errors);
+                    if let Some(autocomplete) = &attributes.autocomplete {
+                        errors.push(autocomplete_in_command_options(autocomplete));
+                    }
+                    OptionParameter {
+                        binding: ident,
+                        ty: &field.ty,
+                        name: match &attributes.rename {
+                            Some(rename) => rename.clone(),
+                            None => LitStr::new(&ident.unraw().to_string(), ident.span()),
+                        },
+                        attributes,
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let fields = option_parameters
+                .iter()
+                .map(|parameter| parameter.binding)
+                .collect::<Vec<_>>();
+            let option_names = option_names(&option_parameters);
+            let command_options = command_options(&option_parameters);
+            let resolve_parameters = resolve_parameters(&option_parameters);
+            let description = optional_description(attributes.description.as_deref());
+
+            let resolver = if fields.is_empty() {
+                quote! {
+                    ::std::boxed::Box::new(|_http, _options| {
+                        ::std::boxed::Box::pin(::std::future::ready(
+                            ::std::result::Result::Ok(Self::#ident {}),
+                        ))
+                    })
+                }
+            } else {
+                quote! {
+                    ::std::boxed::Box::new(|http, options| {
+                        ::std::boxed::Box::pin(async move {
+                            let mut options = ::tranquil::resolve::find_options(#option_names, options);
+                            #resolve_parameters
+                            ::std::result::Result::Ok(Self::#ident { #(#fields),* })
+                        })
+                    })
+                }
+            };
+
+            quote! {
+                subcommands.push(::tranquil::resolve::Subcommand {
+                    group: ::std::option::Option::None,
+                    group_description: ::std::option::Option::None,
+                    name: #subcommand_name,
+                    description: #description,
+                    options: #command_options,
+                    resolver: #resolver,
+                });
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let subcommand_group = (!has_groups).then(|| {
+        quote! {
+            impl #impl_generics ::tranquil::resolve::SubcommandGroup for #name #ty_generics #where_clause {}
+        }
+    });
+
+    let mut result = TokenStream::from(quote! {
+        impl #impl_generics ::tranquil::resolve::Subcommands for #name #ty_generics #where_clause {
+            fn subcommands() -> ::std::vec::Vec<::tranquil::resolve::Subcommand<Self>> {
+                let mut subcommands = ::std::vec::Vec::new();
+                #(#subcommands)*
+                subcommands
+            }
+        }
+
+        #subcommand_group
+    });
+    result.extend(errors);
+    result
+}
+
 #[proc_macro_derive(Module)]
 pub fn derive_module(item: TokenStream) -> TokenStream {
     let struct_item = parse_macro_input!(item as ItemStruct);
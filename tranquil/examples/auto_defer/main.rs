@@ -0,0 +1,22 @@
+use anyhow::Result;
+use tranquil::{
+    auto_defer::AutoDefer,
+    bot::Bot,
+    utils::{debug_guilds_from_env, discord_token_from_env, dotenv_if_exists},
+};
+
+mod slow_module;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+    dotenv_if_exists()?;
+
+    Bot::new()
+        .application_command_update(debug_guilds_from_env()?)
+        // Applies to all commands, component interactions and modals.
+        .auto_defer(AutoDefer::ephemeral())
+        .register(slow_module::SlowModule)
+        .run_until_ctrl_c(discord_token_from_env()?)
+        .await
+}
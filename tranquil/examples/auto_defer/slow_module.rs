@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use tranquil::{
+    context::command::CommandCtx,
+    macros::{command_provider, slash},
+    module::Module,
+};
+
+#[derive(Module)]
+pub(crate) struct SlowModule;
+
+#[command_provider]
+impl SlowModule {
+    /// Takes longer than Discord's 3 second limit, but is deferred automatically.
+    #[slash(auto_defer)]
+    async fn slow(&self, ctx: CommandCtx) -> Result<()> {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        // Edits the deferred response, since the interaction was already deferred.
+        ctx.respond(|response| {
+            response.interaction_response_data(|data| data.content("Finally done!"))
+        })
+        .await?;
+        Ok(())
+    }
+}
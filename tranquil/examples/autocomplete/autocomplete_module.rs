@@ -58,6 +58,20 @@ impl AutocompleteModule {
         .await?;
         Ok(())
     }
+
+    #[autocompleter]
+    async fn autocomplete_fruit(&self, ctx: AutocompleteCtx, fruit: String) -> Result<()> {
+        ctx.autocomplete(|response| {
+            ["apple", "banana", "cherry"]
+                .into_iter()
+                .filter(|choice| choice.starts_with(&fruit))
+                .fold(response, |response, choice| {
+                    response.add_string_choice(choice, choice)
+                })
+        })
+        .await?;
+        Ok(())
+    }
 }
 
 #[command_provider]
@@ -99,4 +113,27 @@ impl AutocompleteModule {
         .await?;
         Ok(())
     }
+
+    /// Orders some fruit.
+    #[slash]
+    async fn order(
+        &self,
+        ctx: CommandCtx,
+        #[autocomplete = autocomplete_fruit]
+        #[max_length = 16]
+        fruit: Autocomplete<String>,
+        /// How many to order.
+        #[min = 1]
+        #[max = 99]
+        #[rename = "count"]
+        amount: i64,
+    ) -> Result<()> {
+        ctx.respond(|response| {
+            response.interaction_response_data(|data| {
+                data.content(format!("Ordered {amount}x {}.", fruit.0))
+            })
+        })
+        .await?;
+        Ok(())
+    }
 }
@@ -8,6 +8,7 @@ mod autocomplete_module;
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
     dotenv_if_exists()?;
 
     Bot::new()
@@ -6,6 +6,7 @@ use tranquil::{
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
     dotenv_if_exists()?;
 
     Bot::new()
@@ -0,0 +1,18 @@
+#[path = "../l10n/example_module.rs"]
+mod example_module;
+
+use anyhow::Result;
+use tranquil::bot::Bot;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let commands = Bot::new()
+        .register(example_module::ExampleModule)
+        .export_application_commands()
+        .await?;
+
+    // Print it out as json, e.g. to commit it as a snapshot.
+    println!("{}", serde_json::to_string_pretty(&commands)?);
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use anyhow::Result;
+use serenity::model::{mention::Mentionable, user::User};
+use tranquil::{
+    context::command::CommandCtx,
+    macros::{command_provider, slash},
+    module::Module,
+    resolve::CommandOptions,
+};
+
+/// Options that are shared by all moderation commands.
+#[derive(CommandOptions)]
+struct ModerationOptions {
+    /// The user to moderate.
+    target: User,
+    /// Shown in the audit log.
+    #[max_length = 200]
+    reason: Option<String>,
+}
+
+impl ModerationOptions {
+    fn describe(&self, action: &str) -> String {
+        let target = self.target.mention();
+        match &self.reason {
+            Some(reason) => format!("Would {action} {target} because: {reason}"),
+            None => format!("Would {action} {target}."),
+        }
+    }
+}
+
+#[derive(Module)]
+pub(crate) struct ModerationModule;
+
+#[command_provider]
+impl ModerationModule {
+    /// Warns a user.
+    #[slash]
+    async fn warn(&self, ctx: CommandCtx, #[flatten] options: ModerationOptions) -> Result<()> {
+        respond(ctx, options.describe("warn")).await
+    }
+
+    /// Times out a user for a number of minutes.
+    #[slash]
+    async fn timeout(
+        &self,
+        ctx: CommandCtx,
+        #[flatten] options: ModerationOptions,
+        /// How long the timeout lasts.
+        #[min = 1]
+        #[max = 40320]
+        minutes: Option<i64>,
+    ) -> Result<()> {
+        let minutes = minutes.unwrap_or(5);
+        respond(
+            ctx,
+            options.describe(&format!("time out for {minutes} minutes")),
+        )
+        .await
+    }
+}
+
+async fn respond(ctx: CommandCtx, content: String) -> Result<()> {
+    ctx.respond(|response| {
+        response.interaction_response_data(|data| data.ephemeral(true).content(content))
+    })
+    .await?;
+    Ok(())
+}
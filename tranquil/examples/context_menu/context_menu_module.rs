@@ -0,0 +1,43 @@
+use anyhow::Result;
+use serenity::model::{channel::Message, guild::PartialMember, user::User};
+use tranquil::{
+    context::command::CommandCtx,
+    macros::{command_provider, message_command, user_command},
+    module::Module,
+};
+
+#[derive(Module)]
+pub(crate) struct ContextMenuModule;
+
+#[command_provider]
+impl ContextMenuModule {
+    #[user_command(rename = "Show Avatar")]
+    async fn show_avatar(&self, ctx: CommandCtx, user: User) -> Result<()> {
+        ctx.respond(|response| {
+            response.interaction_response_data(|data| data.content(user.face()).ephemeral(true))
+        })
+        .await?;
+        Ok(())
+    }
+
+    // Only available in guilds, since the target has to be a member.
+    #[user_command(rename = "Show Nickname", dm = false)]
+    async fn show_nickname(&self, ctx: CommandCtx, member: PartialMember) -> Result<()> {
+        let nick = member.nick.unwrap_or_else(|| "no nickname".to_string());
+        ctx.respond(|response| {
+            response.interaction_response_data(|data| data.content(nick).ephemeral(true))
+        })
+        .await?;
+        Ok(())
+    }
+
+    #[message_command(rename = "Count Words")]
+    async fn count_words(&self, ctx: CommandCtx, message: Message) -> Result<()> {
+        let words = message.content.split_whitespace().count();
+        ctx.respond(|response| {
+            response.interaction_response_data(|data| data.content(format!("{words} words")))
+        })
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,29 @@
+use std::net::SocketAddr;
+
+use anyhow::{Context, Result};
+use tranquil::{
+    bot::Bot,
+    utils::{debug_guilds_from_env, discord_token_from_env, dotenv_if_exists},
+};
+
+mod ping_module;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+    dotenv_if_exists()?;
+
+    let addr: SocketAddr = std::env::var("HTTP_INTERACTIONS_ADDR")
+        .as_deref()
+        .unwrap_or("127.0.0.1:8080")
+        .parse()
+        .context("HTTP_INTERACTIONS_ADDR invalid")?;
+    let public_key =
+        std::env::var("DISCORD_PUBLIC_KEY").context("failed to read DISCORD_PUBLIC_KEY")?;
+
+    Bot::new()
+        .application_command_update(debug_guilds_from_env()?)
+        .register(ping_module::PingModule)
+        .serve_http(discord_token_from_env()?, addr, &public_key)
+        .await
+}
@@ -0,0 +1,25 @@
+use anyhow::Result;
+use tranquil::{
+    context::command::CommandCtx,
+    macros::{command_provider, slash},
+    module::Module,
+};
+
+#[derive(Module)]
+pub(crate) struct PingModule;
+
+#[command_provider]
+impl PingModule {
+    #[slash]
+    async fn ping(&self, ctx: CommandCtx) -> Result<()> {
+        // The initial response is sent as the reply to Discord's HTTP request.
+        let ctx = ctx
+            .respond(|response| response.interaction_response_data(|data| data.content("Pong!")))
+            .await?;
+        // Everything afterwards is sent using the regular HTTP API.
+        ctx.followups()
+            .create(|followup| followup.content("...via HTTP!"))
+            .await?;
+        Ok(())
+    }
+}
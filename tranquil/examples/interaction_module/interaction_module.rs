@@ -9,6 +9,7 @@ use tokio::time::sleep;
 use tranquil::{
     button::{Button, ButtonColor, LinkButton},
     context::{command::CommandCtx, component::ComponentCtx},
+    cooldown::Cooldown,
     handle_interactions,
     interaction::Interact,
     macros::{command_provider, slash},
@@ -193,6 +194,7 @@ impl CounterButton {
 #[async_trait]
 impl Interact for CounterButton {
     const UUID: Uuid = uuid!("00269e42-1dd5-4d28-920b-d583d277f042");
+    const COOLDOWN: Option<Cooldown> = Some(Cooldown::per_channel(Duration::from_secs(2)));
 
     type Module = InteractionModule;
 
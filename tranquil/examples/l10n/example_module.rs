@@ -1,9 +1,10 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use serenity::model::user::User;
 use tranquil::{
     context::command::CommandCtx,
     l10n::{L10n, L10nLoadError},
-    macros::{command_provider, slash},
+    macros::{command_provider, slash, user_command},
     module::Module,
     resolve::Choices,
 };
@@ -26,13 +27,32 @@ async fn pong(ctx: CommandCtx) -> Result<()> {
 #[command_provider]
 #[allow(unused_variables)]
 impl ExampleModule {
-    #[slash]
-    async fn members_add(&self, ctx: CommandCtx, member: String) -> Result<()> {
+    // Doc comments and `#[description]` are used when the l10n has no English description.
+
+    /// Add a new member
+    #[slash(cooldown = "10s", per = "user")]
+    async fn members_add(
+        &self,
+        ctx: CommandCtx,
+        /// The member to be added
+        member: String,
+    ) -> Result<()> {
         pong(ctx).await
     }
 
+    /// Change the color of a member
     #[slash]
-    async fn members_color(&self, ctx: CommandCtx, member: String, color: Color) -> Result<()> {
+    async fn members_color(
+        &self,
+        ctx: CommandCtx,
+        #[description = "The member whose color is to be changed"] member: String,
+        #[description = "The new color for the member"] color: Color,
+    ) -> Result<()> {
+        pong(ctx).await
+    }
+
+    #[user_command(rename = "Member Info")]
+    async fn member_info(&self, ctx: CommandCtx, user: User) -> Result<()> {
         pong(ctx).await
     }
 }
@@ -0,0 +1,22 @@
+#[path = "../l10n/example_module.rs"]
+mod example_module;
+mod reload_module;
+
+use anyhow::Result;
+use tranquil::{
+    bot::Bot,
+    utils::{debug_guilds_from_env, discord_token_from_env, dotenv_if_exists},
+};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+    dotenv_if_exists()?;
+
+    Bot::new()
+        .application_command_update(debug_guilds_from_env()?)
+        .register(example_module::ExampleModule)
+        .register(reload_module::ReloadModule)
+        .run_until_ctrl_c(discord_token_from_env()?)
+        .await
+}
@@ -0,0 +1,27 @@
+use anyhow::Result;
+use tranquil::{
+    bot::BotHandle,
+    context::command::CommandCtx,
+    macros::{command_provider, slash},
+    module::Module,
+};
+
+#[derive(Module)]
+pub(crate) struct ReloadModule;
+
+#[command_provider]
+impl ReloadModule {
+    /// Edit `example_module_l10n.yaml` and run this command to see the changes without a restart.
+    #[slash(permissions = "ADMINISTRATOR")]
+    async fn reload(&self, ctx: CommandCtx) -> Result<()> {
+        let content = match ctx.data::<BotHandle>().reload_l10n().await {
+            Ok(()) => "Reloaded l10n.".to_string(),
+            Err(error) => format!("{error:#}"),
+        };
+        ctx.respond(|response| {
+            response.interaction_response_data(|data| data.content(content).ephemeral(true))
+        })
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serenity::{
+    client::Context,
+    model::{
+        event::{GuildMemberAddEvent, MessageCreateEvent},
+        mention::Mentionable,
+    },
+};
+use tranquil::{handle_events, listener::Listener, macros::command_provider, module::Module};
+
+pub(crate) struct ListenerModule;
+
+impl Module for ListenerModule {
+    handle_events![MessageCreateEvent, GuildMemberAddEvent];
+}
+
+#[command_provider]
+impl ListenerModule {}
+
+#[async_trait]
+impl Listener<MessageCreateEvent> for ListenerModule {
+    async fn listen(&self, bot: &Context, event: &MessageCreateEvent) -> Result<()> {
+        if event.message.mentions_me(bot).await? {
+            event.message.react(bot, '👋').await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Listener<GuildMemberAddEvent> for ListenerModule {
+    async fn listen(&self, bot: &Context, event: &GuildMemberAddEvent) -> Result<()> {
+        let member = &event.member;
+        if let Some(channel) = member
+            .guild_id
+            .to_partial_guild(bot)
+            .await?
+            .system_channel_id
+        {
+            channel
+                .say(bot, format!("Welcome, {}!", member.mention()))
+                .await?;
+        }
+        Ok(())
+    }
+}
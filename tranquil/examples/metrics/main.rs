@@ -0,0 +1,28 @@
+use std::env;
+
+use anyhow::{Context, Result};
+use tranquil::{
+    bot::Bot,
+    utils::{debug_guilds_from_env, discord_token_from_env, dotenv_if_exists},
+};
+
+#[path = "../ping_module/ping_module.rs"]
+mod ping_module;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+    dotenv_if_exists()?;
+
+    let metrics_addr = env::var("METRICS_ADDR")
+        .unwrap_or_else(|_| "127.0.0.1:9100".to_string())
+        .parse()
+        .context("METRICS_ADDR invalid")?;
+
+    Bot::new()
+        .application_command_update(debug_guilds_from_env()?)
+        .metrics_endpoint(metrics_addr)
+        .register(ping_module::PingModule)
+        .run_until_ctrl_c(discord_token_from_env()?)
+        .await
+}
@@ -0,0 +1,54 @@
+use std::time::Instant;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::info;
+use tranquil::{
+    context::command::CommandCtx,
+    macros::{command_provider, slash},
+    middleware::{InteractionCtx, Middleware, Next},
+    module::Module,
+};
+
+/// Logs how long each interaction took to handle.
+pub(crate) struct Timing;
+
+#[async_trait]
+impl Middleware for Timing {
+    async fn handle(&self, ctx: InteractionCtx, next: Next<'_>) -> Result<()> {
+        let user = ctx.user().name.clone();
+        let start = Instant::now();
+        let result = next.run(ctx).await;
+        info!("handled interaction of {user} in {:?}", start.elapsed());
+        result
+    }
+}
+
+#[derive(Module)]
+pub(crate) struct MiddlewareModule;
+
+impl MiddlewareModule {
+    async fn guild_only(&self, ctx: InteractionCtx, next: Next<'_>) -> Result<()> {
+        if ctx.guild_id().is_some() {
+            next.run(ctx).await
+        } else {
+            ctx.respond(|response| {
+                response.interaction_response_data(|data| {
+                    data.content("This only works in guilds.").ephemeral(true)
+                })
+            })
+            .await?;
+            Ok(())
+        }
+    }
+}
+
+#[command_provider(middleware = "guild_only")]
+impl MiddlewareModule {
+    #[slash]
+    async fn guild_ping(&self, ctx: CommandCtx) -> Result<()> {
+        ctx.respond(|response| response.interaction_response_data(|data| data.content("Pong!")))
+            .await?;
+        Ok(())
+    }
+}
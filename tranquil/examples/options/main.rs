@@ -8,6 +8,7 @@ mod echo_module;
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
     dotenv_if_exists()?;
 
     Bot::new()
@@ -0,0 +1,22 @@
+use anyhow::Result;
+use tranquil::{
+    bot::{ApplicationCommandUpdate, Bot},
+    utils::{debug_guilds_from_env_var, discord_token_from_env, dotenv_if_exists},
+};
+
+mod scoped_module;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+    dotenv_if_exists()?;
+
+    Bot::new()
+        .application_command_update(Some(ApplicationCommandUpdate::Global))
+        .register(scoped_module::PublicModule)
+        .register(scoped_module::StaffModule {
+            staff_guilds: debug_guilds_from_env_var("STAFF_GUILDS")?,
+        })
+        .run_until_ctrl_c(discord_token_from_env()?)
+        .await
+}
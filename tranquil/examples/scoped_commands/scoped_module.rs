@@ -0,0 +1,55 @@
+use anyhow::Result;
+use tranquil::{
+    bot::ApplicationCommandUpdate,
+    context::command::CommandCtx,
+    macros::{command_provider, slash},
+    module::Module,
+};
+
+#[derive(Module)]
+pub(crate) struct PublicModule;
+
+#[command_provider]
+impl PublicModule {
+    /// Registered globally, like all commands of the bot by default.
+    #[slash]
+    async fn ping(&self, ctx: CommandCtx) -> Result<()> {
+        respond(ctx, "Pong!").await
+    }
+
+    /// Registered separately in every guild the bot is connected to.
+    #[slash(scope = "connected")]
+    async fn guild_ping(&self, ctx: CommandCtx) -> Result<()> {
+        respond(ctx, "Guild pong!").await
+    }
+}
+
+pub(crate) struct StaffModule {
+    pub(crate) staff_guilds: Option<ApplicationCommandUpdate>,
+}
+
+/// All commands of this module are only registered in the staff guilds.
+impl Module for StaffModule {
+    fn scope(&self) -> Option<ApplicationCommandUpdate> {
+        self.staff_guilds.clone()
+    }
+}
+
+#[command_provider]
+impl StaffModule {
+    #[slash(rename = "staff announce")]
+    async fn staff_announce(&self, ctx: CommandCtx) -> Result<()> {
+        respond(ctx, "Announced!").await
+    }
+
+    #[slash(rename = "staff shutdown")]
+    async fn staff_shutdown(&self, ctx: CommandCtx) -> Result<()> {
+        respond(ctx, "Shutting down...").await
+    }
+}
+
+async fn respond(ctx: CommandCtx, content: &str) -> Result<()> {
+    ctx.respond(|response| response.interaction_response_data(|data| data.content(content)))
+        .await?;
+    Ok(())
+}
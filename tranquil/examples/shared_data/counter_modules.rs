@@ -0,0 +1,43 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::Result;
+use tranquil::{
+    context::command::CommandCtx,
+    macros::{command_provider, slash},
+    module::Module,
+};
+
+/// Shared between both modules using [`Bot::with_data`](tranquil::bot::Bot::with_data).
+pub(crate) struct Counter(pub(crate) AtomicU64);
+
+#[derive(Module)]
+pub(crate) struct IncrementModule;
+
+#[command_provider]
+impl IncrementModule {
+    #[slash]
+    async fn increment(&self, ctx: CommandCtx) -> Result<()> {
+        let count = ctx.data::<Counter>().0.fetch_add(1, Ordering::Relaxed) + 1;
+        ctx.respond(|response| {
+            response.interaction_response_data(|data| data.content(format!("Counter: {count}")))
+        })
+        .await?;
+        Ok(())
+    }
+}
+
+#[derive(Module)]
+pub(crate) struct ShowModule;
+
+#[command_provider]
+impl ShowModule {
+    #[slash]
+    async fn show(&self, ctx: CommandCtx) -> Result<()> {
+        let count = ctx.data::<Counter>().0.load(Ordering::Relaxed);
+        ctx.respond(|response| {
+            response.interaction_response_data(|data| data.content(format!("Counter: {count}")))
+        })
+        .await?;
+        Ok(())
+    }
+}
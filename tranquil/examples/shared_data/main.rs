@@ -0,0 +1,23 @@
+use std::sync::atomic::AtomicU64;
+
+use anyhow::Result;
+use tranquil::{
+    bot::Bot,
+    utils::{debug_guilds_from_env, discord_token_from_env, dotenv_if_exists},
+};
+
+mod counter_modules;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+    dotenv_if_exists()?;
+
+    Bot::new()
+        .application_command_update(debug_guilds_from_env()?)
+        .with_data(counter_modules::Counter(AtomicU64::new(0)))
+        .register(counter_modules::IncrementModule)
+        .register(counter_modules::ShowModule)
+        .run_until_ctrl_c(discord_token_from_env()?)
+        .await
+}
@@ -0,0 +1,62 @@
+use anyhow::Result;
+use tranquil::{
+    context::command::CommandCtx,
+    macros::{command_provider, slash},
+    module::Module,
+    resolve::Subcommands,
+};
+
+#[derive(Subcommands)]
+enum ConfigCommand {
+    /// Shows the value of a setting.
+    Get {
+        /// The name of the setting.
+        key: String,
+    },
+    /// Changes the value of a setting.
+    Set {
+        /// The name of the setting.
+        key: String,
+        /// The new value of the setting.
+        #[max_length = 100]
+        value: String,
+    },
+    /// Resets all settings.
+    ResetAll,
+    /// Manages aliases for settings.
+    Alias(AliasCommand),
+}
+
+#[derive(Subcommands)]
+enum AliasCommand {
+    /// Adds an alias for a setting.
+    Add { alias: String, key: String },
+    /// Removes an alias.
+    Remove { alias: String },
+}
+
+#[derive(Module)]
+pub(crate) struct ConfigModule;
+
+#[command_provider]
+impl ConfigModule {
+    /// Manages the settings of this server.
+    #[slash]
+    async fn config(&self, ctx: CommandCtx, #[subcommands] command: ConfigCommand) -> Result<()> {
+        let content = match command {
+            ConfigCommand::Get { key } => format!("Would show `{key}`."),
+            ConfigCommand::Set { key, value } => format!("Would set `{key}` to `{value}`."),
+            ConfigCommand::ResetAll => "Would reset all settings.".to_string(),
+            ConfigCommand::Alias(AliasCommand::Add { alias, key }) => {
+                format!("Would add `{alias}` as an alias for `{key}`.")
+            }
+            ConfigCommand::Alias(AliasCommand::Remove { alias }) => {
+                format!("Would remove the alias `{alias}`.")
+            }
+        };
+
+        ctx.respond(|response| response.interaction_response_data(|data| data.content(content)))
+            .await?;
+        Ok(())
+    }
+}
@@ -4,15 +4,18 @@ use tranquil::{
     utils::{debug_guilds_from_env, discord_token_from_env, dotenv_if_exists},
 };
 
+mod config_module;
 mod subcommand_module;
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
     dotenv_if_exists()?;
 
     Bot::new()
         .application_command_update(debug_guilds_from_env()?)
         .register(subcommand_module::SubcommandModule)
+        .register(config_module::ConfigModule)
         .run_until_ctrl_c(discord_token_from_env()?)
         .await
 }
@@ -14,14 +14,15 @@ async fn pong(ctx: CommandCtx) -> Result<()> {
     Ok(())
 }
 
-#[command_provider]
+#[command_provider(dm = false)]
 impl SubcommandModule {
     #[slash]
     async fn member_add(&self, ctx: CommandCtx) -> Result<()> {
         pong(ctx).await
     }
 
-    #[slash]
+    // Permissions only apply to the root command, so this also restricts the other `/member` commands.
+    #[slash(permissions = "MANAGE_GUILD")]
     async fn member_delete(&self, ctx: CommandCtx) -> Result<()> {
         pong(ctx).await
     }
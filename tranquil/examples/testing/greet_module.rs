@@ -0,0 +1,73 @@
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tranquil::{
+    button::Button,
+    context::{command::CommandCtx, component::ComponentCtx},
+    handle_interactions,
+    interaction::Interact,
+    macros::{command_provider, slash},
+    module::Module,
+};
+use uuid::{uuid, Uuid};
+
+pub(crate) struct GreetModule;
+
+impl Module for GreetModule {
+    handle_interactions![GreetButton];
+}
+
+#[command_provider]
+impl GreetModule {
+    #[slash]
+    async fn greet_user(&self, ctx: CommandCtx, name: String, times: i64) -> Result<()> {
+        if times < 1 {
+            bail!("cannot greet {name} {times} times");
+        }
+
+        let ctx = ctx
+            .respond(|response| {
+                response.interaction_response_data(|data| {
+                    data.content(format!("Hello, {name}!"))
+                        .components(|components| {
+                            components.create_action_row(|row| {
+                                row.add_button(Button::text("Again!").create(&GreetButton { name }))
+                            })
+                        })
+                })
+            })
+            .await?;
+
+        for _ in 1..times {
+            ctx.followups()
+                .create(|followup| followup.content("Hello again!"))
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct GreetButton {
+    pub(crate) name: String,
+}
+
+#[async_trait]
+impl Interact for GreetButton {
+    const UUID: Uuid = uuid!("2f4d4e8b-4c4e-4b6a-9f0e-6a8d2b3c1e7f");
+
+    type Module = GreetModule;
+
+    async fn interact(self, _module: &Self::Module, ctx: ComponentCtx) -> Result<()> {
+        ctx.respond(|response| {
+            response.interaction_response_data(|data| {
+                data.content(format!("Hello again, {}!", self.name))
+                    .ephemeral(true)
+            })
+        })
+        .await?;
+
+        Ok(())
+    }
+}
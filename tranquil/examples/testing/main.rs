@@ -0,0 +1,53 @@
+use anyhow::Result;
+use tranquil::{bot::Bot, command::CommandPath, testing::TestBot};
+
+mod greet_module;
+
+/// Usually this would be a test, but examples are easier to run on their own.
+#[tokio::main]
+async fn main() -> Result<()> {
+    let bot = TestBot::new(Bot::new().register(greet_module::GreetModule)).await?;
+
+    let outcome = bot
+        .command(greet_user())
+        .option("name", "Ferris")
+        .option("times", 3)
+        .run()
+        .await;
+    assert_eq!(outcome.error, None);
+    assert_eq!(
+        outcome.contents(),
+        ["Hello, Ferris!", "Hello again!", "Hello again!"]
+    );
+
+    let outcome = bot
+        .click(&greet_module::GreetButton {
+            name: "Ferris".to_owned(),
+        })
+        .run()
+        .await;
+    assert_eq!(outcome.contents(), ["Hello again, Ferris!"]);
+    assert!(outcome.responses[0].is_ephemeral());
+
+    let outcome = bot
+        .command(greet_user())
+        .option("name", "Ferris")
+        .option("times", 0)
+        .run()
+        .await;
+    assert_eq!(
+        outcome.error.as_deref(),
+        Some("cannot greet Ferris 0 times")
+    );
+
+    println!("All good!");
+
+    Ok(())
+}
+
+fn greet_user() -> CommandPath {
+    CommandPath::Subcommand {
+        name: "greet".to_owned(),
+        subcommand: "user".to_owned(),
+    }
+}
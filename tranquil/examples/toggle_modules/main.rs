@@ -0,0 +1,25 @@
+#[path = "../ping_module/ping_module.rs"]
+mod ping_module;
+mod toggle_module;
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use tranquil::{
+    bot::Bot,
+    utils::{debug_guilds_from_env, discord_token_from_env, dotenv_if_exists},
+};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+    dotenv_if_exists()?;
+
+    Bot::new()
+        .application_command_update(debug_guilds_from_env()?)
+        .register(toggle_module::ToggleModule {
+            ping: Arc::new(ping_module::PingModule),
+        })
+        .run_until_ctrl_c(discord_token_from_env()?)
+        .await
+}
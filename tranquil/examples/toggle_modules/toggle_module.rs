@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use tranquil::{
+    bot::BotHandle,
+    context::command::CommandCtx,
+    macros::{command_provider, slash},
+    module::Module,
+};
+
+use crate::ping_module::PingModule;
+
+#[derive(Module)]
+pub(crate) struct ToggleModule {
+    pub(crate) ping: Arc<PingModule>,
+}
+
+#[command_provider]
+impl ToggleModule {
+    /// Adds or removes `/ping` without restarting the bot.
+    #[slash(permissions = "ADMINISTRATOR")]
+    async fn toggle_ping(&self, ctx: CommandCtx, enabled: bool) -> Result<()> {
+        let bot = ctx.data::<BotHandle>();
+        let content = if enabled {
+            match bot.add_module(self.ping.clone()).await {
+                Ok(()) => "Enabled `/ping`.".to_string(),
+                Err(error) => format!("{error:#}"),
+            }
+        } else {
+            match bot.remove_module(&self.ping).await {
+                Ok(true) => "Disabled `/ping`.".to_string(),
+                Ok(false) => "`/ping` is already disabled.".to_string(),
+                Err(error) => format!("{error:#}"),
+            }
+        };
+        ctx.respond(|response| {
+            response.interaction_response_data(|data| data.content(content).ephemeral(true))
+        })
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use std::time::Duration;
+
+/// Defers interactions that were not responded to in time.
+///
+/// Discord requires a response within 3 seconds. Once deferred, the first response of the handler
+/// is sent as an edit of the deferred response instead.
+///
+/// Commands are deferred with a new message. Components, as well as modals that were opened from a
+/// component, are deferred as an update of their message instead, where `ephemeral` has no effect.
+/// If their handler still responds with a new message, it is sent as a followup.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct AutoDefer {
+    pub after: Duration,
+    pub ephemeral: bool,
+}
+
+impl AutoDefer {
+    /// Leaves enough time for the deferral itself to reach Discord.
+    pub const DEFAULT_AFTER: Duration = Duration::from_secs(2);
+
+    pub fn new() -> Self {
+        Self {
+            after: Self::DEFAULT_AFTER,
+            ephemeral: false,
+        }
+    }
+
+    pub fn ephemeral() -> Self {
+        Self {
+            after: Self::DEFAULT_AFTER,
+            ephemeral: true,
+        }
+    }
+}
+
+impl Default for AutoDefer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,47 +1,69 @@
 use std::{
-    collections::{hash_map::Entry, HashMap},
-    mem::take,
+    any::Any,
+    collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap},
     ops::Deref,
+    panic::AssertUnwindSafe,
+    pin::Pin,
     sync::{
         atomic::{self, AtomicBool},
-        Arc,
+        Arc, OnceLock, Weak,
     },
+    time::Duration,
 };
 
 use anyhow::{anyhow, bail, Context, Result};
+use arc_swap::ArcSwap;
 use async_trait::async_trait;
-use futures::{future::join_all, join};
+use futures::{
+    future::{self, join_all},
+    Future, FutureExt,
+};
 use itertools::chain;
 use serenity::{
     builder::CreateApplicationCommand,
     client::{EventHandler, RawEventHandler},
-    http::Http,
+    http::{GuildPagination, Http},
     model::{
         application::{
             command::{Command, CommandOptionType},
             component::ComponentType,
-            interaction::Interaction,
+            interaction::{Interaction, InteractionResponseType},
         },
         event::Event,
         gateway::{GatewayIntents, Ready},
         guild::{Guild, UnavailableGuild},
-        id::GuildId,
+        id::{GuildId, InteractionId},
     },
     utils::colours as colors,
     Client,
 };
+use tracing::{error, info, info_span, trace, Instrument, Span};
 use uuid::Uuid;
 
 use crate::{
-    command::{CommandMap, CommandMapEntry, CommandPath, SubcommandMapEntry},
+    auto_defer::AutoDefer,
+    command::{
+        command_scope, CommandMap, CommandMapEntry, CommandPath, CommandPermissions,
+        SubcommandMapEntry,
+    },
+    command_diff::ApplicationCommandDiff,
+    command_validation::validate_application_commands,
     context::{
         autocomplete::AutocompleteCtx, command::CommandCtx, component::ComponentCtx,
         modal::ModalCtx,
     },
+    cooldown::{Cooldown, CooldownBackend, CooldownKey, CooldownScope, InMemoryCooldownBackend},
+    data::Data,
+    error_handler::{DefaultErrorHandler, ErrorHandler, InteractionError, InteractionOrigin},
+    initial_response::InitialResponse,
     l10n::{CommandPathRef, L10n},
+    middleware::{InteractionCtx, Middleware, Next},
     module::Module,
 };
 
+/// Where application commands are registered.
+///
+/// This is set for the whole [`Bot`], but can be overridden by each [`Module`] and command.
 #[derive(Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ApplicationCommandUpdate {
     #[default]
@@ -52,87 +74,29 @@ pub enum ApplicationCommandUpdate {
 
 type CustomIdMap = HashMap<Uuid, Arc<dyn Module>>;
 
-pub struct Bot {
-    already_connected: AtomicBool,
-    application_command_update: Option<ApplicationCommandUpdate>,
+/// The registered modules, together with everything that is derived from them.
+///
+/// Always replaced as a whole, so that interactions never see a partially updated state.
+#[derive(Default)]
+struct LoadedModules {
+    modules: Vec<Arc<dyn Module>>,
     command_map: CommandMap,
     custom_id_map: CustomIdMap,
-    modules: Vec<Arc<dyn Module>>,
     l10n: L10n,
 }
 
-impl Default for Bot {
-    fn default() -> Self {
-        Self {
-            already_connected: Default::default(),
-            application_command_update: Some(ApplicationCommandUpdate::default()),
-            command_map: Default::default(),
-            custom_id_map: Default::default(),
-            modules: Default::default(),
-            l10n: Default::default(),
-        }
-    }
-}
-
-pub trait IntoArcModule {
-    fn into_arc_module(self) -> Arc<dyn Module>;
-}
-
-impl<T: Module + 'static> IntoArcModule for T {
-    fn into_arc_module(self) -> Arc<dyn Module> {
-        Arc::new(self)
-    }
-}
-
-impl<T: Module + 'static> IntoArcModule for Arc<T> {
-    fn into_arc_module(self) -> Arc<dyn Module> {
-        self
-    }
-}
-
-impl Bot {
-    pub fn new() -> Self {
-        Default::default()
-    }
-
-    pub fn application_command_update(
-        mut self,
-        application_command_update: Option<ApplicationCommandUpdate>,
-    ) -> Self {
-        self.application_command_update = application_command_update;
-        self
-    }
-
-    pub fn register(mut self, module: impl IntoArcModule) -> Self {
-        self.modules.push(module.into_arc_module());
-        self
-    }
-
-    pub async fn run(mut self, discord_token: impl AsRef<str>) -> Result<()> {
-        // TODO: Token validation doesn't work, because of the middle "timestamp" part not always
-        // being valid base64.
-        // validate_token(&token).map_err(|err| {
-        //     eprintln!("{err}");
-        //     err
-        // });
-
-        self.command_map = self.load_command_map()?;
-        self.custom_id_map = self.load_custom_id_map()?;
-        self.l10n = self.load_l10n().await?;
-
-        let intents = merge_intents(self.modules.iter().map(Deref::deref));
-
-        Client::builder(discord_token, intents)
-            .event_handler(self)
-            .await?
-            .start()
-            .await?;
-
-        Ok(())
+impl LoadedModules {
+    async fn load(modules: Vec<Arc<dyn Module>>) -> Result<Self> {
+        Ok(Self {
+            command_map: Self::load_command_map(&modules)?,
+            custom_id_map: Self::load_custom_id_map(&modules)?,
+            l10n: Self::load_l10n(&modules).await?,
+            modules,
+        })
     }
 
-    fn load_command_map(&self) -> Result<CommandMap> {
-        self.modules.iter().try_fold(
+    fn load_command_map(modules: &[Arc<dyn Module>]) -> Result<CommandMap> {
+        modules.iter().try_fold(
             Default::default(),
             |command_map, module| -> Result<CommandMap> {
                 Ok(command_map.merge(module.clone().command_map()?)?)
@@ -140,9 +104,9 @@ impl Bot {
         )
     }
 
-    fn load_custom_id_map(&self) -> Result<CustomIdMap> {
+    fn load_custom_id_map(modules: &[Arc<dyn Module>]) -> Result<CustomIdMap> {
         let mut custom_id_map = CustomIdMap::new();
-        for module in &self.modules {
+        for module in modules {
             for &uuid in chain(module.interaction_uuids(), module.modal_uuids()) {
                 match custom_id_map.entry(uuid) {
                     Entry::Vacant(entry) => entry.insert(module.clone()),
@@ -153,40 +117,49 @@ impl Bot {
         Ok(custom_id_map)
     }
 
-    async fn load_l10n(&self) -> Result<L10n> {
-        L10n::merge_results(join_all(self.modules.iter().map(|module| module.l10n())).await)
-            .map_err(|error| {
-                eprintln!("{error}");
+    async fn load_l10n(modules: &[Arc<dyn Module>]) -> Result<L10n> {
+        L10n::merge_results(join_all(modules.iter().map(|module| module.l10n())).await).map_err(
+            |error| {
+                error!("{error}");
                 anyhow!("invalid l10n")
-            })
-    }
-
-    pub async fn run_until_ctrl_c(self, discord_token: impl AsRef<str>) -> Result<()> {
-        tokio::select! {
-            result = self.run(discord_token) => result?,
-            result = tokio::signal::ctrl_c() => result?,
-        }
-
-        Ok(())
+            },
+        )
     }
 
-    fn create_application_commands(&self) -> Vec<CreateApplicationCommand> {
+    /// Creates all application commands together with their scope, if it differs from the bot's.
+    fn create_application_commands(
+        &self,
+    ) -> Vec<(Option<ApplicationCommandUpdate>, CreateApplicationCommand)> {
+        let l10n = &self.l10n;
         self.command_map
             .iter()
             .map(|(name, command)| {
                 let mut application_command = CreateApplicationCommand::default();
 
-                self.l10n.describe_command(name, &mut application_command);
+                l10n.describe_command(name, command.description(), &mut application_command);
+
+                command_permissions(command).apply(&mut application_command);
 
                 match command {
                     CommandMapEntry::Command(command) => {
-                        command.add_options(&self.l10n, &mut application_command);
+                        command.add_options(
+                            l10n,
+                            CommandPathRef::Command { name },
+                            &mut application_command,
+                        );
                     }
                     CommandMapEntry::Subcommands(subcommands) => {
                         for (subcommand, entry) in subcommands {
                             application_command.create_option(|option| {
-                                self.l10n.describe_subcommand(
+                                let description = match entry {
+                                    SubcommandMapEntry::Subcommand(command) => {
+                                        command.description()
+                                    }
+                                    SubcommandMapEntry::Group(group) => group.description(),
+                                };
+                                l10n.describe_subcommand(
                                     CommandPathRef::Subcommand { name, subcommand },
+                                    description,
                                     option,
                                 );
 
@@ -196,19 +169,24 @@ impl Bot {
                                             .kind(CommandOptionType::SubCommand)
                                             .default_option(command.is_default_option());
 
-                                        command.add_suboptions(&self.l10n, option);
+                                        command.add_suboptions(
+                                            l10n,
+                                            CommandPathRef::Subcommand { name, subcommand },
+                                            option,
+                                        );
                                     }
                                     SubcommandMapEntry::Group(command_map) => {
                                         let group = subcommand;
                                         option.kind(CommandOptionType::SubCommandGroup);
                                         for (subcommand, command) in command_map {
                                             option.create_sub_option(|option| {
-                                                self.l10n.describe_subcommand(
+                                                l10n.describe_subcommand(
                                                     CommandPathRef::Grouped {
                                                         name,
                                                         group,
                                                         subcommand,
                                                     },
+                                                    command.description(),
                                                     option,
                                                 );
 
@@ -216,7 +194,15 @@ impl Bot {
                                                     .kind(CommandOptionType::SubCommand)
                                                     .default_option(command.is_default_option());
 
-                                                command.add_suboptions(&self.l10n, option);
+                                                command.add_suboptions(
+                                                    l10n,
+                                                    CommandPathRef::Grouped {
+                                                        name,
+                                                        group,
+                                                        subcommand,
+                                                    },
+                                                    option,
+                                                );
 
                                                 option
                                             });
@@ -230,10 +216,472 @@ impl Bot {
                     }
                 }
 
-                application_command
+                (command.scope(), application_command)
             })
+            .chain(
+                self.command_map
+                    .context_menu_commands()
+                    .map(|(kind, name, command)| {
+                        let mut application_command = CreateApplicationCommand::default();
+                        l10n.describe_context_menu_command(kind, name, &mut application_command);
+                        single_command_permissions(command).apply(&mut application_command);
+                        (command_scope(command), application_command)
+                    }),
+            )
             .collect()
     }
+}
+
+/// A handle to a running [`Bot`].
+///
+/// Available as [`Data`] from every context, e.g. using `ctx.data::<BotHandle>()`.
+#[derive(Clone, Debug)]
+pub struct BotHandle {
+    bot: Weak<Bot>,
+}
+
+impl BotHandle {
+    fn bot(&self) -> Result<Arc<Bot>> {
+        self.bot
+            .upgrade()
+            .ok_or_else(|| anyhow!("bot is no longer running"))
+    }
+
+    /// See [`Bot::reload_l10n`].
+    pub async fn reload_l10n(&self) -> Result<()> {
+        self.bot()?.reload_l10n().await
+    }
+
+    /// Registers an additional module while the bot is running.
+    ///
+    /// Its commands are added to Discord right away, but since the gateway connection already
+    /// exists, neither its [`Module::intents`] nor [`Module::on_ready`] have any effect.
+    ///
+    /// If the module cannot be added, e.g. because of conflicting commands, the previous modules
+    /// are kept and the error is returned.
+    pub async fn add_module(&self, module: impl IntoArcModule) -> Result<()> {
+        let module = module.into_arc_module();
+        self.bot()?
+            .update_modules(|modules| modules.push(module))
+            .await
+            .context("failed to add module, keeping the previous ones")
+    }
+
+    /// Unregisters a module that was registered using an [`Arc`], calling its
+    /// [`Module::on_shutdown`].
+    ///
+    /// Returns `false` if the module was not registered.
+    pub async fn remove_module<T: Module + 'static>(&self, module: &Arc<T>) -> Result<bool> {
+        let removed = self
+            .bot()?
+            .update_modules(|modules| {
+                let index = modules.iter().position(|registered| {
+                    Arc::as_ptr(registered).cast::<()>() == Arc::as_ptr(module).cast()
+                })?;
+                Some(modules.remove(index))
+            })
+            .await
+            .context("failed to remove module, keeping the previous ones")?;
+
+        let Some(removed) = removed else {
+            return Ok(false);
+        };
+        shutdown_modules(&[removed]).await;
+        Ok(true)
+    }
+}
+
+pub struct Bot {
+    already_connected: AtomicBool,
+    application_command_update: Option<ApplicationCommandUpdate>,
+    /// Modules that are registered before the bot runs, moved into `loaded` once it does.
+    modules: Vec<Arc<dyn Module>>,
+    loaded: ArcSwap<LoadedModules>,
+    module_update: tokio::sync::Mutex<()>,
+    /// Guilds that application commands were registered for by the last update.
+    ///
+    /// Locked for the whole update, so that updates cannot interleave.
+    synced_guilds: tokio::sync::Mutex<BTreeSet<GuildId>>,
+    error_handler: Box<dyn ErrorHandler>,
+    data: Arc<Data>,
+    middlewares: Vec<Arc<dyn Middleware>>,
+    cooldown_backend: Box<dyn CooldownBackend>,
+    auto_defer: Option<AutoDefer>,
+    http: OnceLock<Arc<Http>>,
+    #[cfg(feature = "metrics")]
+    metrics: Arc<crate::metrics::Metrics>,
+    #[cfg(feature = "metrics")]
+    metrics_endpoint: Option<std::net::SocketAddr>,
+}
+
+impl Default for Bot {
+    fn default() -> Self {
+        Self {
+            already_connected: Default::default(),
+            application_command_update: Some(ApplicationCommandUpdate::default()),
+            modules: Default::default(),
+            loaded: Default::default(),
+            module_update: Default::default(),
+            synced_guilds: Default::default(),
+            error_handler: Box::new(DefaultErrorHandler),
+            data: Default::default(),
+            middlewares: Default::default(),
+            cooldown_backend: Box::new(InMemoryCooldownBackend::new()),
+            auto_defer: None,
+            http: Default::default(),
+            #[cfg(feature = "metrics")]
+            metrics: Default::default(),
+            #[cfg(feature = "metrics")]
+            metrics_endpoint: None,
+        }
+    }
+}
+
+pub trait IntoArcModule {
+    fn into_arc_module(self) -> Arc<dyn Module>;
+}
+
+impl<T: Module + 'static> IntoArcModule for T {
+    fn into_arc_module(self) -> Arc<dyn Module> {
+        Arc::new(self)
+    }
+}
+
+impl<T: Module + 'static> IntoArcModule for Arc<T> {
+    fn into_arc_module(self) -> Arc<dyn Module> {
+        self
+    }
+}
+
+impl Bot {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn application_command_update(
+        mut self,
+        application_command_update: Option<ApplicationCommandUpdate>,
+    ) -> Self {
+        self.application_command_update = application_command_update;
+        self
+    }
+
+    pub fn register(mut self, module: impl IntoArcModule) -> Self {
+        self.modules.push(module.into_arc_module());
+        self
+    }
+
+    /// Replaces the [`DefaultErrorHandler`], which is called for every failed interaction.
+    pub fn on_error(mut self, error_handler: impl ErrorHandler + 'static) -> Self {
+        self.error_handler = Box::new(error_handler);
+        self
+    }
+
+    /// Adds a [`Middleware`] that runs around every command, component interaction and modal.
+    ///
+    /// Middlewares run in the order they were added, before the middleware of the module itself.
+    pub fn middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    /// Replaces the [`InMemoryCooldownBackend`], e.g. to share cooldowns between multiple shards.
+    pub fn cooldown_backend(mut self, cooldown_backend: impl CooldownBackend + 'static) -> Self {
+        self.cooldown_backend = Box::new(cooldown_backend);
+        self
+    }
+
+    /// Replaces the default [`Metrics`](crate::metrics::Metrics), e.g. to use a custom registry.
+    ///
+    /// Clones of the metrics share their values, so a clone can be kept to access them.
+    #[cfg(feature = "metrics")]
+    pub fn metrics(mut self, metrics: crate::metrics::Metrics) -> Self {
+        self.metrics = Arc::new(metrics);
+        self
+    }
+
+    /// Serves the [`Metrics`](crate::metrics::Metrics) in the Prometheus text format.
+    #[cfg(feature = "metrics")]
+    pub fn metrics_endpoint(mut self, addr: std::net::SocketAddr) -> Self {
+        self.metrics_endpoint = Some(addr);
+        self
+    }
+
+    /// Automatically defers all commands, component interactions and modal submissions.
+    ///
+    /// Commands can override this using e.g. `#[slash(auto_defer = "ephemeral")]`.
+    pub fn auto_defer(mut self, auto_defer: AutoDefer) -> Self {
+        self.auto_defer = Some(auto_defer);
+        self
+    }
+
+    /// Registers a value that is shared by all modules and accessible from every context.
+    ///
+    /// Only one value per type can be registered; registering the same type again replaces it.
+    pub fn with_data<T: Send + Sync + 'static>(mut self, value: T) -> Self {
+        Arc::get_mut(&mut self.data)
+            .expect("data should not be shared before the bot runs")
+            .insert(value);
+        self
+    }
+
+    pub async fn run(self, discord_token: impl AsRef<str>) -> Result<()> {
+        self.run_until(discord_token, future::pending()).await
+    }
+
+    pub async fn run_until_ctrl_c(self, discord_token: impl AsRef<str>) -> Result<()> {
+        self.run_until(discord_token, async { Ok(tokio::signal::ctrl_c().await?) })
+            .await
+    }
+
+    /// Runs the bot until it stops on its own or `stop` completes, then shuts down all modules.
+    async fn run_until(
+        mut self,
+        discord_token: impl AsRef<str>,
+        stop: impl Future<Output = Result<()>>,
+    ) -> Result<()> {
+        // TODO: Token validation doesn't work, because of the middle "timestamp" part not always
+        // being valid base64.
+        // validate_token(&token).map_err(|err| {
+        //     error!("{err}");
+        //     err
+        // });
+
+        self.load().await?;
+
+        #[cfg(feature = "metrics")]
+        let _metrics_endpoint = self.spawn_metrics_endpoint()?;
+
+        let intents = merge_intents(self.loaded.load().modules.iter().map(Deref::deref));
+        let bot = self.into_shared();
+
+        let client = async {
+            Client::builder(discord_token, intents)
+                .event_handler_arc(bot.clone())
+                .raw_event_handler(SharedBot(bot.clone()))
+                .await?
+                .start()
+                .await
+        };
+
+        let result = tokio::select! {
+            result = client => result.map_err(Into::into),
+            result = stop => result,
+        };
+
+        shutdown_modules(&bot.loaded.load().modules).await;
+
+        result
+    }
+
+    /// Logs how the registered application commands differ from the current ones.
+    ///
+    /// Only uses the HTTP API without connecting to the gateway and never updates any commands,
+    /// which makes it useful to check for pending changes in CI.
+    pub async fn diff_application_commands(mut self, discord_token: impl AsRef<str>) -> Result<()> {
+        self.load().await?;
+
+        let http = Http::new(discord_token.as_ref());
+        http.set_application_id(http.get_current_application_info().await?.id.0);
+
+        let connected_guilds = self.fetch_connected_guilds(&http).await?;
+
+        self.update_application_commands(&http, &connected_guilds, true)
+            .await;
+
+        Ok(())
+    }
+
+    /// Returns the JSON payload of all application commands, as it would be sent to Discord.
+    ///
+    /// Commands are sorted by name, which makes the output suitable for snapshots.
+    pub async fn export_application_commands(mut self) -> Result<serde_json::Value> {
+        self.load().await?;
+
+        Ok(serde_json::Value::Array(
+            self.create_application_commands()
+                .into_iter()
+                .map(|(_, application_command)| serde_json::to_value(application_command.0))
+                .collect::<serde_json::Result<_>>()?,
+        ))
+    }
+
+    /// Receives interactions via an HTTP endpoint instead of connecting to the gateway.
+    ///
+    /// Discord has to be pointed at this endpoint using the "Interactions Endpoint URL" of the
+    /// application, which also provides the `public_key` that is used to verify each request.
+    ///
+    /// Since there is no gateway connection, gateway events like [`Module::on_ready`] are never
+    /// received.
+    #[cfg(feature = "http-interactions")]
+    pub async fn serve_http(
+        mut self,
+        discord_token: impl AsRef<str>,
+        addr: std::net::SocketAddr,
+        public_key: &str,
+    ) -> Result<()> {
+        let public_key = crate::http_interactions::parse_public_key(public_key)?;
+
+        self.load().await?;
+
+        #[cfg(feature = "metrics")]
+        let _metrics_endpoint = self.spawn_metrics_endpoint()?;
+
+        let server = crate::http_interactions::HttpInteractions::bind(addr, public_key)?;
+        let http = Http::new(discord_token.as_ref());
+        http.set_application_id(http.get_current_application_info().await?.id.0);
+
+        let connected_guilds = self.fetch_connected_guilds(&http).await?;
+        self.update_application_commands(&http, &connected_guilds, false)
+            .await;
+
+        let http = Arc::new(http);
+        self.http.get_or_init(|| http.clone());
+
+        let bot = self.into_shared();
+        let result = server.serve(bot.clone(), http).await;
+
+        shutdown_modules(&bot.loaded.load().modules).await;
+
+        result
+    }
+
+    async fn fetch_connected_guilds(&self, http: &Http) -> Result<Vec<GuildId>> {
+        /// The maximum number of guilds that Discord returns at once.
+        const GUILD_PAGE_LIMIT: u64 = 200;
+
+        let mut guilds = vec![];
+        if self.uses_connected_guilds() {
+            loop {
+                let pagination = guilds.last().copied().map(GuildPagination::After);
+                let page = http
+                    .get_guilds(pagination.as_ref(), Some(GUILD_PAGE_LIMIT))
+                    .await?;
+                let page_len = page.len();
+                guilds.extend(page.into_iter().map(|guild| guild.id));
+                if page_len < GUILD_PAGE_LIMIT as usize {
+                    break;
+                }
+            }
+        }
+        Ok(guilds)
+    }
+
+    fn uses_connected_guilds(&self) -> bool {
+        match &self.application_command_update {
+            Some(ApplicationCommandUpdate::Connected) => true,
+            Some(_) => self
+                .create_application_commands()
+                .iter()
+                .any(|(scope, _)| scope == &Some(ApplicationCommandUpdate::Connected)),
+            None => false,
+        }
+    }
+
+    /// Collects the commands, interactions and translations of all registered modules.
+    pub(crate) async fn load(&mut self) -> Result<()> {
+        let modules = std::mem::take(&mut self.modules);
+        self.loaded
+            .store(Arc::new(self.load_modules(modules).await?));
+        Ok(())
+    }
+
+    /// Loads the modules and validates their application commands against the limits of Discord.
+    async fn load_modules(&self, modules: Vec<Arc<dyn Module>>) -> Result<LoadedModules> {
+        let loaded = LoadedModules::load(modules).await?;
+
+        let default_scope = self.application_command_update.as_ref();
+        validate_application_commands(loaded.create_application_commands().iter().map(
+            |(scope, application_command)| {
+                let global = default_scope.is_some()
+                    && scope.as_ref().or(default_scope) == Some(&ApplicationCommandUpdate::Global);
+                (global, application_command)
+            },
+        ))?;
+
+        Ok(loaded)
+    }
+
+    /// Starts serving metrics in the background, if an endpoint was configured.
+    ///
+    /// The endpoint stops once the returned guard is dropped.
+    #[cfg(feature = "metrics")]
+    fn spawn_metrics_endpoint(&self) -> Result<Option<AbortOnDrop>> {
+        let Some(addr) = self.metrics_endpoint else {
+            return Ok(None);
+        };
+        let listener = std::net::TcpListener::bind(addr)
+            .with_context(|| format!("failed to bind metrics endpoint to {addr}"))?;
+        let metrics = self.metrics.clone();
+        Ok(Some(AbortOnDrop(tokio::spawn(async move {
+            if let Err(error) = crate::metrics::serve(listener, metrics).await {
+                error!("{:?}", error.context("metrics endpoint stopped"));
+            }
+        }))))
+    }
+
+    /// Moves the bot into an [`Arc`] and makes its [`BotHandle`] available as [`Data`].
+    pub(crate) fn into_shared(mut self) -> Arc<Self> {
+        Arc::new_cyclic(|bot| {
+            Arc::get_mut(&mut self.data)
+                .expect("data should not be shared before the bot runs")
+                .insert(BotHandle { bot: bot.clone() });
+            self
+        })
+    }
+
+    /// Reloads the l10n of all modules and updates the application commands accordingly.
+    ///
+    /// If the new l10n fails to load, the previous one is kept and the error is returned.
+    ///
+    /// Use [`BotHandle::reload_l10n`] to call this while the bot is running.
+    pub async fn reload_l10n(&self) -> Result<()> {
+        self.update_modules(|_| {})
+            .await
+            .context("failed to reload l10n, keeping the previous one")?;
+        info!("Reloaded l10n");
+        Ok(())
+    }
+
+    /// Reloads all modules after applying `update` to them and updates the application commands.
+    ///
+    /// Updates are serialized, so that concurrent updates cannot overwrite each other.
+    async fn update_modules<T>(
+        &self,
+        update: impl FnOnce(&mut Vec<Arc<dyn Module>>) -> T,
+    ) -> Result<T> {
+        let _update = self.module_update.lock().await;
+
+        let mut modules = self.loaded.load().modules.clone();
+        let result = update(&mut modules);
+        self.loaded
+            .store(Arc::new(self.load_modules(modules).await?));
+
+        // The modules are already replaced at this point, so failing to sync is only logged.
+        if let Some(http) = self.http.get() {
+            match self.fetch_connected_guilds(http).await {
+                Ok(connected_guilds) => {
+                    self.update_application_commands(http, &connected_guilds, false)
+                        .await;
+                }
+                Err(error) => error!("{:?}", error.context("failed to fetch connected guilds")),
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Replaces the error handler with the [`DefaultErrorHandler`], returning the previous one.
+    #[cfg(feature = "testing")]
+    pub(crate) fn take_error_handler(&mut self) -> Box<dyn ErrorHandler> {
+        std::mem::replace(&mut self.error_handler, Box::new(DefaultErrorHandler))
+    }
+
+    fn create_application_commands(
+        &self,
+    ) -> Vec<(Option<ApplicationCommandUpdate>, CreateApplicationCommand)> {
+        self.loaded.load().create_application_commands()
+    }
 
     fn notify_connect(&self, bot_name: &str, guild_count: usize) -> bool {
         let first_connect = !self.already_connected.swap(true, atomic::Ordering::AcqRel);
@@ -243,29 +691,60 @@ impl Bot {
             "Reconnected"
         };
         let s = if guild_count == 1 { "" } else { "s" };
-        println!("{connected} as {bot_name} to {guild_count} guild{s}",);
+        info!("{connected} as {bot_name} to {guild_count} guild{s}");
         first_connect
     }
 
-    async fn update_application_commands(&self, http: &Http, global_guilds: &[UnavailableGuild]) {
+    async fn update_application_commands(
+        &self,
+        http: &Http,
+        connected_guilds: &[GuildId],
+        dry_run: bool,
+    ) {
         if let Some(application_command_update) = &self.application_command_update {
-            update_application_commands(
+            let mut synced_guilds = self.synced_guilds.lock().await;
+            let registered_guilds = ApplicationCommandTargets::new(
                 application_command_update,
                 self.create_application_commands(),
-                http,
-                global_guilds,
+                connected_guilds,
+                &synced_guilds,
             )
+            .update(http, dry_run)
             .await;
+            if !dry_run {
+                *synced_guilds = registered_guilds;
+            }
         } else {
-            println!("Skipping updating of application commands");
+            info!("Skipping updating of application commands");
         }
     }
 
     async fn handle_command(&self, ctx: CommandCtx) -> Result<()> {
         let command_path = CommandPath::resolve(&ctx.interaction.data);
 
-        match self.command_map.find_command(&command_path) {
-            Some(command) => command.run(ctx).await?,
+        let loaded = self.loaded.load_full();
+        match loaded.command_map.find_command(&command_path) {
+            Some(command) => {
+                let _auto_defer = spawn_auto_defer(
+                    command.auto_defer().or(self.auto_defer),
+                    InteractionResponseType::DeferredChannelMessageWithSource,
+                    &ctx.initial_response,
+                    &ctx.bot,
+                    ctx.interaction.id,
+                    &ctx.interaction.token,
+                );
+                let cooldown = command
+                    .cooldown()
+                    .map(|cooldown| (command_path.to_string(), cooldown));
+                self.run_middlewares(command.module(), cooldown, ctx.into(), |ctx| {
+                    Box::pin(async move {
+                        command
+                            .run(ctx.try_into().map_err(|_| changed_interaction_kind())?)
+                            .await
+                    })
+                })
+                .await?
+            }
             None => {
                 ctx.respond(|response| {
                     response.interaction_response_data(|data| {
@@ -302,14 +781,35 @@ impl Bot {
             })
     }
 
-    async fn handle_component(&self, mut ctx: ComponentCtx) -> Result<()> {
+    async fn handle_component(&self, ctx: ComponentCtx) -> Result<()> {
         match ctx.interaction.data.component_type {
             ComponentType::Button | ComponentType::SelectMenu => {
-                let custom_id = take(&mut ctx.interaction.data.custom_id);
+                let custom_id = ctx.interaction.data.custom_id.clone();
                 let (uuid, state) = self.parse_custom_id(&custom_id)?;
-                self.resolve_custom_id_module(uuid)?
-                    .interact(uuid, state, ctx)
-                    .await?;
+                let module = &self.resolve_custom_id_module(uuid)?;
+                let _auto_defer = spawn_auto_defer(
+                    self.auto_defer,
+                    InteractionResponseType::DeferredUpdateMessage,
+                    &ctx.initial_response,
+                    &ctx.bot,
+                    ctx.interaction.id,
+                    &ctx.interaction.token,
+                );
+                let cooldown = module
+                    .interaction_cooldown(uuid)
+                    .map(|cooldown| (uuid.to_string(), cooldown));
+                self.run_middlewares(&**module, cooldown, ctx.into(), |ctx| {
+                    Box::pin(async move {
+                        module
+                            .interact(
+                                uuid,
+                                state,
+                                ctx.try_into().map_err(|_| changed_interaction_kind())?,
+                            )
+                            .await
+                    })
+                })
+                .await?;
             }
             _ => {}
         }
@@ -320,7 +820,8 @@ impl Bot {
     async fn handle_autocomplete(&self, ctx: AutocompleteCtx) -> Result<()> {
         let command_path = CommandPath::resolve(&ctx.interaction.data);
 
-        match self.command_map.find_command(&command_path) {
+        let loaded = self.loaded.load_full();
+        match loaded.command_map.find_command(&command_path) {
             Some(command) => command.autocomplete(ctx).await?,
             None => {
                 // Commands are probably outdated... Send an empty autocomplete response.
@@ -331,31 +832,224 @@ impl Bot {
         Ok(())
     }
 
-    async fn handle_modal(&self, mut ctx: ModalCtx) -> Result<()> {
-        let custom_id = take(&mut ctx.interaction.data.custom_id);
+    async fn handle_modal(&self, ctx: ModalCtx) -> Result<()> {
+        let custom_id = ctx.interaction.data.custom_id.clone();
         let (uuid, state) = self.parse_custom_id(&custom_id)?;
-        self.resolve_custom_id_module(uuid)?
-            .submit(uuid, state, ctx)
+        let module = &self.resolve_custom_id_module(uuid)?;
+        // Only modals that were opened from a component have a message that can be updated.
+        let kind = if ctx.interaction.message.is_some() {
+            InteractionResponseType::DeferredUpdateMessage
+        } else {
+            InteractionResponseType::DeferredChannelMessageWithSource
+        };
+        let _auto_defer = spawn_auto_defer(
+            self.auto_defer,
+            kind,
+            &ctx.initial_response,
+            &ctx.bot,
+            ctx.interaction.id,
+            &ctx.interaction.token,
+        );
+        self.run_middlewares(&**module, None, ctx.into(), |ctx| {
+            Box::pin(async move {
+                module
+                    .submit(
+                        uuid,
+                        state,
+                        ctx.try_into().map_err(|_| changed_interaction_kind())?,
+                    )
+                    .await
+            })
+        })
+        .await
+    }
+
+    /// Runs the middlewares of the bot, followed by the middleware of the module and the handler.
+    ///
+    /// The cooldown is only checked right before the handler, so that interactions that were
+    /// rejected by a middleware do not count towards it.
+    async fn run_middlewares<'a>(
+        &'a self,
+        module: &'a dyn Module,
+        cooldown: Option<(String, Cooldown)>,
+        ctx: InteractionCtx,
+        handler: impl FnOnce(InteractionCtx) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>
+            + Send
+            + 'a,
+    ) -> Result<()> {
+        let handler = move |ctx: InteractionCtx| -> Pin<Box<dyn Future<Output = _> + Send + 'a>> {
+            Box::pin(async move {
+                if let Some((name, cooldown)) = cooldown {
+                    if let Some(remaining) = self.start_cooldown(name, cooldown, &ctx).await? {
+                        return self.respond_cooldown(ctx, remaining).await;
+                    }
+                }
+                handler(ctx).await
+            })
+        };
+
+        Next::new(
+            &self.middlewares,
+            Box::new(move |ctx| module.middleware(ctx, Next::from_fn(handler))),
+        )
+        .run(ctx)
+        .await
+    }
+
+    async fn start_cooldown(
+        &self,
+        name: String,
+        cooldown: Cooldown,
+        ctx: &InteractionCtx,
+    ) -> Result<Option<Duration>> {
+        let id = match cooldown.scope {
+            CooldownScope::User => ctx.user().id.0,
+            CooldownScope::Guild => ctx.guild_id().map_or(ctx.channel_id().0, |guild| guild.0),
+            CooldownScope::Channel => ctx.channel_id().0,
+            CooldownScope::Global => 0,
+        };
+        self.cooldown_backend
+            .try_start(
+                CooldownKey {
+                    name,
+                    scope: cooldown.scope,
+                    id,
+                },
+                cooldown.duration,
+            )
             .await
     }
 
-    fn resolve_custom_id_module(&self, uuid: Uuid) -> Result<&Arc<dyn Module>> {
-        self.custom_id_map
+    async fn respond_cooldown(&self, ctx: InteractionCtx, remaining: Duration) -> Result<()> {
+        let seconds = remaining.as_millis().div_ceil(1000);
+        let message = self
+            .loaded
+            .load()
+            .l10n
+            .message("cooldown", ctx.locale())
+            .unwrap_or("You are on cooldown, try again in {seconds}s.")
+            .replace("{seconds}", &seconds.to_string());
+
+        ctx.respond(|response| {
+            response.interaction_response_data(|data| {
+                data.embed(|embed| embed.color(colors::css::WARNING).description(message))
+                    .ephemeral(true)
+            })
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    async fn guild_create(&self, bot: &serenity::client::Context, guild: &Guild) {
+        join_all(self.loaded.load_full().modules.iter().map(|module| async {
+            if let Err(error) = module.on_guild_create(bot, guild).await {
+                error!("{:?}", error.context("error during on_guild_create"));
+            }
+        }))
+        .await;
+    }
+
+    async fn guild_delete(&self, bot: &serenity::client::Context, guild: &UnavailableGuild) {
+        join_all(self.loaded.load_full().modules.iter().map(|module| async {
+            if let Err(error) = module.on_guild_delete(bot, guild).await {
+                error!("{:?}", error.context("error during on_guild_delete"));
+            }
+        }))
+        .await;
+    }
+
+    async fn dispatch(
+        &self,
+        bot: serenity::client::Context,
+        interaction: Interaction,
+        initial_response: Arc<InitialResponse>,
+    ) -> Result<()> {
+        match interaction {
+            Interaction::Ping(_) => Ok(()),
+            Interaction::ApplicationCommand(interaction) => {
+                self.handle_command(CommandCtx {
+                    bot,
+                    data: self.data.clone(),
+                    initial_response,
+                    interaction,
+                })
+                .await
+            }
+            Interaction::MessageComponent(interaction) => {
+                self.handle_component(ComponentCtx {
+                    bot,
+                    data: self.data.clone(),
+                    initial_response,
+                    interaction,
+                })
+                .await
+            }
+            Interaction::Autocomplete(interaction) => {
+                self.handle_autocomplete(AutocompleteCtx {
+                    bot,
+                    data: self.data.clone(),
+                    initial_response,
+                    interaction,
+                })
+                .await
+            }
+            Interaction::ModalSubmit(interaction) => {
+                self.handle_modal(ModalCtx {
+                    bot,
+                    data: self.data.clone(),
+                    initial_response,
+                    interaction,
+                })
+                .await
+            }
+        }
+    }
+
+    fn interaction_origin(&self, interaction: &Interaction) -> Option<InteractionOrigin> {
+        match interaction {
+            Interaction::Ping(_) => None,
+            Interaction::ApplicationCommand(interaction) => Some(InteractionOrigin::Command(
+                CommandPath::resolve(&interaction.data),
+            )),
+            Interaction::MessageComponent(interaction) => Some(InteractionOrigin::Component(
+                self.parse_custom_id(&interaction.data.custom_id)
+                    .ok()
+                    .map(|(uuid, _)| uuid),
+            )),
+            Interaction::Autocomplete(interaction) => Some(InteractionOrigin::Autocomplete(
+                CommandPath::resolve(&interaction.data),
+            )),
+            Interaction::ModalSubmit(interaction) => Some(InteractionOrigin::Modal(
+                self.parse_custom_id(&interaction.data.custom_id)
+                    .ok()
+                    .map(|(uuid, _)| uuid),
+            )),
+        }
+    }
+
+    fn resolve_custom_id_module(&self, uuid: Uuid) -> Result<Arc<dyn Module>> {
+        self.loaded
+            .load()
+            .custom_id_map
             .get(&uuid)
+            .cloned()
             .ok_or_else(|| anyhow!("no module that handles the custom_id uuid {uuid}"))
     }
 }
 
-type GuildUpdateError = (String, Result<(), serenity::Error>);
+type GuildUpdateError = (String, Result<ApplicationCommandDiff, serenity::Error>);
 
 fn print_application_command_update_errors(
     guild_update_errors: impl Iterator<Item = GuildUpdateError>,
+    dry_run: bool,
 ) {
-    for (guild, error) in guild_update_errors {
-        if let Err(error) = error {
-            eprintln!(" ⚠ {guild}\n   ▶ {error}");
-        } else {
-            println!(" ✓ {guild}");
+    for (guild, diff) in guild_update_errors {
+        match diff {
+            Err(error) => error!(%guild, %error, "failed to update application commands"),
+            Ok(diff) if diff.is_empty() => info!(%guild, "application commands are up to date"),
+            Ok(diff) if dry_run => info!(%guild, "application commands would change:\n{diff}"),
+            Ok(diff) => info!(%guild, "updated application commands:\n{diff}"),
         }
     }
 }
@@ -363,166 +1057,516 @@ fn print_application_command_update_errors(
 async fn update_application_commands_globally(
     http: &Http,
     application_commands: Vec<CreateApplicationCommand>,
+    dry_run: bool,
 ) {
     let command_count = application_commands.len();
-    println!(
+    info!(
         "Updating {command_count} application command{} globally...",
         if command_count == 1 { "" } else { "s" }
     );
     print_application_command_update_errors(
         [(
             "globally".to_owned(),
-            Command::set_global_application_commands(http, |commands| {
-                commands.set_application_commands(application_commands)
-            })
-            .await
-            .map(|_| ()),
+            update_global(application_commands, http, dry_run).await,
         )]
         .into_iter(),
+        dry_run,
     );
 }
 
-async fn update_application_commands_for_connected_guilds(
+/// Returns the guilds that have application commands registered afterwards.
+async fn update_application_commands_for_guilds(
     http: &Http,
-    application_commands: Vec<CreateApplicationCommand>,
-    connected_guilds: &[UnavailableGuild],
-) {
-    let command_count = application_commands.len();
-    let guild_count = connected_guilds.len();
-    println!(
-        "Updating {command_count} application command{} for all {guild_count} connected guild{}...",
-        if command_count == 1 { "" } else { "s" },
+    guilds: BTreeMap<GuildId, Vec<CreateApplicationCommand>>,
+    dry_run: bool,
+) -> BTreeSet<GuildId> {
+    let guild_count = guilds.len();
+    info!(
+        "Updating application commands for {guild_count} guild{}...",
         if guild_count == 1 { "" } else { "s" },
     );
+    let mut registered_guilds = guilds
+        .iter()
+        .filter(|(_, commands)| !commands.is_empty())
+        .map(|(&guild, _)| guild)
+        .collect::<BTreeSet<_>>();
+    let guild_updates = update_guilds(http, guilds, dry_run).await;
+    // Guilds that failed to update might still have commands, so they are retried next time.
+    registered_guilds.extend(
+        guild_updates
+            .iter()
+            .filter(|(_, (_, diff))| diff.is_err())
+            .map(|&(guild, _)| guild),
+    );
     print_application_command_update_errors(
-        update_guilds(
-            application_commands,
-            http,
-            connected_guilds.iter().map(|guild| guild.id),
-        )
-        .await,
+        guild_updates.into_iter().map(|(_, update)| update),
+        dry_run,
     );
+    registered_guilds
 }
 
-async fn update_application_commands_for(
-    http: &Http,
-    application_commands: Vec<CreateApplicationCommand>,
-    guilds: &[GuildId],
-) {
-    let command_count = application_commands.len();
-    let guild_count = guilds.len();
-    println!(
-        "Updating {command_count} application command{} only for the {guild_count} specified guild{}...",
-        if command_count == 1 { "" } else { "s" },
-        if guild_count == 1 { "" } else { "s" },
-    );
-    print_application_command_update_errors(
-        update_guilds(application_commands, http, guilds.iter().copied()).await,
-    );
+/// The application commands that are registered globally and for each guild.
+///
+/// Only guilds that are part of a scope are updated, as well as guilds that had commands
+/// registered by a previous update, so that their outdated commands are removed.
+#[derive(Default)]
+struct ApplicationCommandTargets {
+    global: Option<Vec<CreateApplicationCommand>>,
+    guilds: BTreeMap<GuildId, Vec<CreateApplicationCommand>>,
 }
 
-async fn update_application_commands(
-    application_command_update: &ApplicationCommandUpdate,
-    application_commands: Vec<CreateApplicationCommand>,
-    http: &Http,
-    connected_guilds: &[UnavailableGuild],
-) {
-    match application_command_update {
-        ApplicationCommandUpdate::Global => {
-            update_application_commands_globally(http, application_commands).await
-        }
-        ApplicationCommandUpdate::Connected => {
-            update_application_commands_for_connected_guilds(
-                http,
-                application_commands,
+impl ApplicationCommandTargets {
+    fn new(
+        default_scope: &ApplicationCommandUpdate,
+        application_commands: Vec<(Option<ApplicationCommandUpdate>, CreateApplicationCommand)>,
+        connected_guilds: &[GuildId],
+        synced_guilds: &BTreeSet<GuildId>,
+    ) -> Self {
+        let mut targets = Self::default();
+        // The default scope is always updated, so that outdated commands are removed from it.
+        targets.add(default_scope, connected_guilds, None);
+        for (scope, application_command) in application_commands {
+            targets.add(
+                scope.as_ref().unwrap_or(default_scope),
                 connected_guilds,
-            )
-            .await
+                Some(application_command),
+            );
+        }
+        for &guild in synced_guilds {
+            targets.guilds.entry(guild).or_default();
         }
-        ApplicationCommandUpdate::Only(guilds) => {
-            update_application_commands_for(http, application_commands, guilds).await
+        targets
+    }
+
+    fn add(
+        &mut self,
+        scope: &ApplicationCommandUpdate,
+        connected_guilds: &[GuildId],
+        application_command: Option<CreateApplicationCommand>,
+    ) {
+        let guilds = match scope {
+            ApplicationCommandUpdate::Global => {
+                self.global
+                    .get_or_insert_with(Vec::new)
+                    .extend(application_command);
+                return;
+            }
+            ApplicationCommandUpdate::Connected => connected_guilds,
+            ApplicationCommandUpdate::Only(guilds) => guilds,
+        };
+        for &guild in guilds {
+            self.guilds
+                .entry(guild)
+                .or_default()
+                .extend(application_command.clone());
+        }
+    }
+
+    /// Returns the guilds that have application commands registered afterwards.
+    async fn update(self, http: &Http, dry_run: bool) -> BTreeSet<GuildId> {
+        if let Some(application_commands) = self.global {
+            update_application_commands_globally(http, application_commands, dry_run).await;
+        }
+        if self.guilds.is_empty() {
+            BTreeSet::new()
+        } else {
+            update_application_commands_for_guilds(http, self.guilds, dry_run).await
         }
     }
 }
 
+async fn update_global(
+    create_application_commands: Vec<CreateApplicationCommand>,
+    http: &Http,
+    dry_run: bool,
+) -> Result<ApplicationCommandDiff, serenity::Error> {
+    let diff = ApplicationCommandDiff::new(
+        &Command::get_global_application_commands_with_localizations(http).await?,
+        &create_application_commands,
+    );
+    if !dry_run && !diff.is_empty() {
+        Command::set_global_application_commands(http, |commands| {
+            commands.set_application_commands(create_application_commands)
+        })
+        .await?;
+    }
+    Ok(diff)
+}
+
+async fn update_guild(
+    create_application_commands: Vec<CreateApplicationCommand>,
+    http: &Http,
+    guild: GuildId,
+    dry_run: bool,
+) -> Result<ApplicationCommandDiff, serenity::Error> {
+    let diff = ApplicationCommandDiff::new(
+        &guild
+            .get_application_commands_with_localizations(http)
+            .await?,
+        &create_application_commands,
+    );
+    if !dry_run && !diff.is_empty() {
+        guild
+            .set_application_commands(http, |commands| {
+                commands.set_application_commands(create_application_commands)
+            })
+            .await?;
+    }
+    Ok(diff)
+}
+
 async fn update_guilds(
-    create_application_commands: impl Into<Vec<CreateApplicationCommand>>,
     http: &Http,
-    guilds: impl Iterator<Item = GuildId> + Clone,
-) -> impl Iterator<Item = GuildUpdateError> {
-    let create_application_commands = create_application_commands.into();
-    let guild_names = join_all(guilds.clone().map(|guild| async move {
-        Guild::get(http, guild)
-            .await
-            .map(|guild| guild.name)
-            .unwrap_or_else(|_| format!("<{}>", guild.0))
-    }));
-    let guild_updates = join_all(guilds.clone().map(|guild| {
-        let create_application_commands = create_application_commands.clone();
-        async move {
-            guild
-                .set_application_commands(http, |commands| {
-                    commands.set_application_commands(create_application_commands)
-                })
+    guilds: BTreeMap<GuildId, Vec<CreateApplicationCommand>>,
+    dry_run: bool,
+) -> Vec<(GuildId, GuildUpdateError)> {
+    join_all(guilds.into_iter().map(|(guild, commands)| async move {
+        let diff = update_guild(commands, http, guild, dry_run).await;
+        // Only guilds worth looking at cost an additional request for their name.
+        let name = match &diff {
+            Ok(diff) if diff.is_empty() => format!("<{}>", guild.0),
+            _ => Guild::get(http, guild)
                 .await
-                .map(|_| ())
-        }
-    }));
-    let (guild_names, guild_updates) = join!(guild_names, guild_updates);
-    guild_names.into_iter().zip(guild_updates.into_iter())
+                .map(|guild| guild.name)
+                .unwrap_or_else(|_| format!("<{}>", guild.0)),
+        };
+        (guild, (name, diff))
+    }))
+    .await
 }
 
 #[async_trait]
 impl EventHandler for Bot {
     async fn ready(&self, bot: serenity::client::Context, data_about_bot: Ready) {
+        self.http.get_or_init(|| bot.http.clone());
         if self.notify_connect(&data_about_bot.user.name, data_about_bot.guilds.len()) {
-            self.update_application_commands(&bot.http, &data_about_bot.guilds)
+            let connected_guilds = data_about_bot
+                .guilds
+                .iter()
+                .map(|guild| guild.id)
+                .collect::<Vec<_>>();
+            self.update_application_commands(&bot.http, &connected_guilds, false)
                 .await;
         }
-        println!("Ready!");
-        println!();
+        join_all(self.loaded.load_full().modules.iter().map(|module| async {
+            if let Err(error) = module.on_ready(&bot, &data_about_bot).await {
+                error!("{:?}", error.context("error during on_ready"));
+            }
+        }))
+        .await;
+        info!("Ready!");
     }
 
     async fn interaction_create(&self, bot: serenity::client::Context, interaction: Interaction) {
+        let Some(origin) = self.interaction_origin(&interaction) else {
+            return;
+        };
+
+        let span = interaction_span(&interaction, &origin);
+        let initial_response = InitialResponse::current();
+
         async {
-            match interaction {
-                Interaction::Ping(_) => {}
-                Interaction::ApplicationCommand(interaction) => {
-                    self.handle_command(CommandCtx { bot, interaction }).await?;
-                }
-                Interaction::MessageComponent(interaction) => {
-                    self.handle_component(ComponentCtx { bot, interaction })
-                        .await?;
-                }
-                Interaction::Autocomplete(interaction) => {
-                    self.handle_autocomplete(AutocompleteCtx { bot, interaction })
-                        .await?;
-                }
-                Interaction::ModalSubmit(interaction) => {
-                    self.handle_modal(ModalCtx { bot, interaction }).await?;
-                }
-            }
+            #[cfg(feature = "metrics")]
+            let started = std::time::Instant::now();
 
-            Ok(())
+            // Panics are reported like any other error, rather than silently dropping the interaction.
+            let result = AssertUnwindSafe(self.dispatch(
+                bot.clone(),
+                interaction.clone(),
+                initial_response.clone(),
+            ))
+            .catch_unwind()
+            .await
+            .unwrap_or_else(|panic| Err(panic_error(panic)));
+
+            #[cfg(feature = "metrics")]
+            self.metrics.record(
+                &origin,
+                if result.is_ok() { "ok" } else { "error" },
+                started.elapsed(),
+            );
+
+            if let Err(error) = result {
+                // Lets the error handler respond through the same initial response.
+                initial_response
+                    .scope(self.error_handler.handle_error(
+                        bot,
+                        InteractionError {
+                            error,
+                            origin,
+                            interaction,
+                        },
+                    ))
+                    .await;
+            }
         }
-        .await
-        .unwrap_or_else(|error: anyhow::Error| {
-            let error = error.context("error during interaction");
-            eprintln!(" ⚠  {:?}", error);
-            eprintln!();
-        });
+        .instrument(span)
+        .await;
     }
 }
 
 #[async_trait]
 impl RawEventHandler for Bot {
-    async fn raw_event(&self, _bot: serenity::client::Context, event: Event) {
-        println!("{event:#?}");
+    async fn raw_event(&self, bot: serenity::client::Context, event: Event) {
+        trace!(?event);
+
+        // Dispatched from here, since the signatures of these `EventHandler` methods depend on
+        // whether serenity's `cache` feature is enabled.
+        match &event {
+            Event::GuildCreate(event) => self.guild_create(&bot, &event.guild).await,
+            Event::GuildDelete(event) => self.guild_delete(&bot, &event.guild).await,
+            _ => {}
+        }
+
+        join_all(self.loaded.load_full().modules.iter().map(|module| async {
+            if let Err(error) = module.handle_event(&bot, &event).await {
+                error!("{:?}", error.context("error during event"));
+            }
+        }))
+        .await;
     }
 }
 
+/// Defers the interaction once the delay of the [`AutoDefer`] passes, unless the returned guard
+/// was dropped by then.
+fn spawn_auto_defer(
+    auto_defer: Option<AutoDefer>,
+    kind: InteractionResponseType,
+    initial_response: &Arc<InitialResponse>,
+    bot: &serenity::client::Context,
+    interaction_id: InteractionId,
+    token: &str,
+) -> Option<AbortOnDrop> {
+    let auto_defer = auto_defer?;
+    let initial_response = initial_response.clone();
+    let http = bot.http.clone();
+    let token = token.to_owned();
+    Some(AbortOnDrop(tokio::spawn(async move {
+        tokio::time::sleep(auto_defer.after).await;
+        if let Err(error) = initial_response
+            .auto_defer(&http, interaction_id, &token, kind, auto_defer.ephemeral)
+            .await
+        {
+            error!("failed to automatically defer interaction: {error}");
+        }
+    })))
+}
+
+struct AbortOnDrop(tokio::task::JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Allows registering the same [`Bot`] as both event handler and raw event handler.
+struct SharedBot(Arc<Bot>);
+
+#[async_trait]
+impl RawEventHandler for SharedBot {
+    async fn raw_event(&self, bot: serenity::client::Context, event: Event) {
+        self.0.raw_event(bot, event).await;
+    }
+}
+
+async fn shutdown_modules(modules: &[Arc<dyn Module>]) {
+    join_all(modules.iter().map(|module| async {
+        if let Err(error) = module.on_shutdown().await {
+            error!("{:?}", error.context("error during on_shutdown"));
+        }
+    }))
+    .await;
+}
+
 fn merge_intents<'a>(modules: impl Iterator<Item = &'a dyn Module>) -> GatewayIntents {
     modules.fold(GatewayIntents::empty(), |acc, module| {
-        acc | module.intents()
+        acc | module.intents() | module.event_intents()
     })
 }
+
+/// The permissions of a command, merged from all of its subcommands.
+fn single_command_permissions(command: &dyn crate::command::Command) -> CommandPermissions {
+    command.permissions().or(command.module().permissions())
+}
+
+fn command_permissions(entry: &CommandMapEntry) -> CommandPermissions {
+    match entry {
+        CommandMapEntry::Command(command) => single_command_permissions(command.as_ref()),
+        CommandMapEntry::Subcommands(subcommands) => subcommands
+            .into_iter()
+            .flat_map(|(_, entry)| match entry {
+                SubcommandMapEntry::Subcommand(command) => {
+                    vec![single_command_permissions(command.as_ref())]
+                }
+                SubcommandMapEntry::Group(commands) => commands
+                    .into_iter()
+                    .map(|(_, command)| single_command_permissions(command.as_ref()))
+                    .collect(),
+            })
+            .reduce(CommandPermissions::restrict)
+            .unwrap_or_default(),
+    }
+}
+
+fn panic_error(panic: Box<dyn Any + Send>) -> anyhow::Error {
+    let message = panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic");
+    anyhow!("handler panicked: {message}")
+}
+
+fn changed_interaction_kind() -> anyhow::Error {
+    anyhow!("middleware changed the kind of interaction")
+}
+
+fn interaction_span(interaction: &Interaction, origin: &InteractionOrigin) -> Span {
+    let (user, guild) = match interaction {
+        Interaction::Ping(_) => (None, None),
+        Interaction::ApplicationCommand(interaction) => {
+            (Some(&interaction.user), interaction.guild_id)
+        }
+        Interaction::MessageComponent(interaction) => {
+            (Some(&interaction.user), interaction.guild_id)
+        }
+        Interaction::Autocomplete(interaction) => (Some(&interaction.user), interaction.guild_id),
+        Interaction::ModalSubmit(interaction) => (Some(&interaction.user), interaction.guild_id),
+    };
+
+    info_span!(
+        "interaction",
+        id = interaction.id().0,
+        user = user.map(|user| user.id.0),
+        guild = guild.map(|guild| guild.0),
+        %origin,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+    use crate::{
+        command::CommandProvider,
+        context::command::CommandCtx,
+        l10n::Locale,
+        macros::{command_provider, slash},
+        resolve::Subcommands,
+    };
+
+    #[derive(Module)]
+    struct SettingsModule;
+
+    #[derive(Subcommands)]
+    enum SettingsCommand {
+        /// Shows a setting.
+        Get { key: String },
+        /// Manages aliases.
+        Alias(AliasCommand),
+    }
+
+    #[derive(Subcommands)]
+    enum AliasCommand {
+        /// Adds an alias.
+        Add { alias: String },
+    }
+
+    #[command_provider]
+    impl SettingsModule {
+        /// Manages settings.
+        #[slash]
+        async fn settings(
+            &self,
+            ctx: CommandCtx,
+            #[subcommands] command: SettingsCommand,
+        ) -> Result<()> {
+            let content = match command {
+                SettingsCommand::Get { key } => key,
+                SettingsCommand::Alias(AliasCommand::Add { alias }) => alias,
+            };
+            ctx.respond(|response| {
+                response.interaction_response_data(|data| data.content(content))
+            })
+            .await?;
+            Ok(())
+        }
+    }
+
+    fn option<'a>(command: &'a Value, name: &str) -> &'a Value {
+        command["options"]
+            .as_array()
+            .and_then(|options| options.iter().find(|option| option["name"] == name))
+            .unwrap_or_else(|| panic!("missing option {name}"))
+    }
+
+    #[tokio::test]
+    async fn describes_subcommand_trees() {
+        let commands = Bot::new()
+            .register(SettingsModule)
+            .export_application_commands()
+            .await
+            .unwrap();
+
+        let settings = &commands[0];
+        assert_eq!(settings["description"], "Manages settings.");
+        assert_eq!(option(settings, "get")["description"], "Shows a setting.");
+        let alias = option(settings, "alias");
+        assert_eq!(alias["description"], "Manages aliases.");
+        assert_eq!(option(alias, "add")["description"], "Adds an alias.");
+    }
+
+    #[test]
+    fn clears_previously_synced_guilds_outside_of_scopes() {
+        let mut ping = CreateApplicationCommand::default();
+        ping.name("ping");
+        let targets = ApplicationCommandTargets::new(
+            &ApplicationCommandUpdate::Global,
+            vec![(Some(ApplicationCommandUpdate::Only(vec![GuildId(1)])), ping)],
+            &[GuildId(1), GuildId(2), GuildId(3)],
+            &BTreeSet::from([GuildId(1), GuildId(2)]),
+        );
+
+        assert_eq!(targets.global.map(|commands| commands.len()), Some(0));
+        let guilds = targets
+            .guilds
+            .iter()
+            .map(|(&guild, commands)| (guild, commands.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(guilds, [(GuildId(1), 1), (GuildId(2), 0)]);
+    }
+
+    #[test]
+    fn skips_connected_guilds_for_global_commands() {
+        let mut ping = CreateApplicationCommand::default();
+        ping.name("ping");
+        let targets = ApplicationCommandTargets::new(
+            &ApplicationCommandUpdate::Global,
+            vec![(None, ping)],
+            &[GuildId(1), GuildId(2)],
+            &BTreeSet::new(),
+        );
+
+        assert_eq!(targets.global.map(|commands| commands.len()), Some(1));
+        assert!(targets.guilds.is_empty());
+    }
+
+    #[test]
+    fn prefills_subcommand_tree_stubs() {
+        let command_map = Arc::new(SettingsModule).command_map().unwrap();
+        let stubs = L10n::command_stubs(&command_map, Locale::EnglishUS | Locale::German).unwrap();
+        let stubs = serde_yaml::from_str::<Value>(&stubs.to_yaml().unwrap()).unwrap();
+
+        let settings = &stubs["commands"]["settings"];
+        assert_eq!(settings["description"]["en-US"], "Manages settings.");
+        assert_eq!(settings["description"]["de"], "TODO");
+        let alias = &settings["subcommands"]["alias"];
+        assert_eq!(alias["description"]["en-US"], "Manages aliases.");
+        assert_eq!(
+            alias["subcommands"]["add"]["description"]["en-US"],
+            "Adds an alias."
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{btree_map::Entry, BTreeMap},
     fmt::{Debug, Display},
     mem::take,
     pin::Pin,
@@ -11,18 +11,28 @@ use async_trait::async_trait;
 use futures::Future;
 use serenity::{
     builder::{CreateApplicationCommand, CreateApplicationCommandOption},
-    model::application::{
-        command::CommandOptionType,
-        interaction::application_command::{CommandData, CommandDataOption},
+    http::Http,
+    model::{
+        application::{
+            command::{CommandOptionType, CommandType},
+            interaction::application_command::{CommandData, CommandDataOption},
+        },
+        permissions::Permissions,
     },
+    utils::colours as colors,
 };
 use thiserror::Error;
 
 use crate::{
+    auto_defer::AutoDefer,
     autocomplete::AutocompleteFunction,
+    bot::ApplicationCommandUpdate,
     context::{autocomplete::AutocompleteCtx, command::CommandCtx},
-    l10n::L10n,
+    cooldown::Cooldown,
+    l10n::{CommandPathRef, L10n},
+    middleware::{InteractionCtx, Next},
     module::Module,
+    resolve::InvalidParameters,
 };
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -39,6 +49,14 @@ pub enum CommandPath {
         group: String,
         subcommand: String,
     },
+    /// A context-menu command on users.
+    User {
+        name: String,
+    },
+    /// A context-menu command on messages.
+    Message {
+        name: String,
+    },
 }
 
 impl CommandPath {
@@ -46,11 +64,32 @@ impl CommandPath {
         match self {
             CommandPath::Command { name }
             | CommandPath::Subcommand { name, .. }
-            | CommandPath::Grouped { name, .. } => name,
+            | CommandPath::Grouped { name, .. }
+            | CommandPath::User { name }
+            | CommandPath::Message { name } => name,
         }
     }
 
+    /// Whether this is a slash command, as opposed to a context-menu command.
+    pub fn is_slash(&self) -> bool {
+        !matches!(self, CommandPath::User { .. } | CommandPath::Message { .. })
+    }
+
     pub(crate) fn resolve(command_data: &CommandData) -> CommandPath {
+        match command_data.kind {
+            CommandType::User => {
+                return CommandPath::User {
+                    name: command_data.name.clone(),
+                }
+            }
+            CommandType::Message => {
+                return CommandPath::Message {
+                    name: command_data.name.clone(),
+                }
+            }
+            _ => {}
+        }
+
         match command_data.options.as_slice() {
             [group]
                 if group.kind == CommandOptionType::SubCommand
@@ -87,6 +126,64 @@ impl Display for CommandPath {
                 group,
                 subcommand,
             } => write!(f, "{name} {group} {subcommand}"),
+            CommandPath::User { name } | CommandPath::Message { name } => write!(f, "{name}"),
+        }
+    }
+}
+
+/// A top-level slash command without any subcommands.
+impl From<&str> for CommandPath {
+    fn from(name: &str) -> Self {
+        CommandPath::Command {
+            name: name.to_owned(),
+        }
+    }
+}
+
+/// Restricts who can see and use a command by default.
+///
+/// Unset values fall back to the ones of the module, which are set using `#[command_provider]`.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct CommandPermissions {
+    pub default_member_permissions: Option<Permissions>,
+    pub dm_permission: Option<bool>,
+}
+
+impl CommandPermissions {
+    pub fn or(self, fallback: Self) -> Self {
+        Self {
+            default_member_permissions: self
+                .default_member_permissions
+                .or(fallback.default_member_permissions),
+            dm_permission: self.dm_permission.or(fallback.dm_permission),
+        }
+    }
+
+    /// Combines the permissions of subcommands that share the same root command.
+    ///
+    /// Discord only supports permissions on root commands, so the most restrictive permissions win.
+    pub fn restrict(self, other: Self) -> Self {
+        Self {
+            default_member_permissions: match (
+                self.default_member_permissions,
+                other.default_member_permissions,
+            ) {
+                (Some(permissions), Some(other)) => Some(permissions | other),
+                (permissions, other) => permissions.or(other),
+            },
+            dm_permission: match (self.dm_permission, other.dm_permission) {
+                (Some(dm_permission), Some(other)) => Some(dm_permission && other),
+                (dm_permission, other) => dm_permission.or(other),
+            },
+        }
+    }
+
+    pub fn apply(&self, command: &mut CreateApplicationCommand) {
+        if let Some(permissions) = self.default_member_permissions {
+            command.default_member_permissions(permissions);
+        }
+        if let Some(dm_permission) = self.dm_permission {
+            command.dm_permission(dm_permission);
         }
     }
 }
@@ -94,21 +191,67 @@ impl Display for CommandPath {
 type CommandFunction<M> = Box<
     dyn Fn(
             Arc<M>,
-            CommandCtx,
+            Arc<Http>,
             Vec<CommandDataOption>,
-        ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>>
+        ) -> Pin<Box<dyn Future<Output = CommandResolveResult> + Send>>
         + Send
         + Sync,
 >;
 
+type CommandResolveResult = Result<CommandHandler, InvalidParameters>;
+
+/// A command with all of its parameters already resolved, only waiting for its context.
+pub type CommandHandler =
+    Box<dyn FnOnce(CommandCtx) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> + Send>;
+
 pub type OptionBuilder = fn(&L10n) -> CreateApplicationCommandOption;
 
+/// A parameter of a command, which is turned into an option using its [`OptionBuilder`].
+pub struct CommandOption {
+    pub name: String,
+    /// Used if the [`L10n`] has no description for the default locale.
+    pub description: Option<&'static str>,
+    pub builder: OptionBuilder,
+}
+
+impl CommandOption {
+    /// Builds the option, including its name and description in all locales.
+    pub fn build(&self, l10n: &L10n, path: CommandPathRef) -> CreateApplicationCommandOption {
+        let mut option = (self.builder)(l10n);
+        l10n.describe_command_option(path, &self.name, self.description, &mut option);
+        option
+    }
+}
+
 pub struct ModuleCommand<M: Module> {
     module: Arc<M>,
     command_function: CommandFunction<M>,
     autocomplete_function: Option<AutocompleteFunction<M>>,
-    options: Vec<(String, OptionBuilder)>,
+    description: Option<&'static str>,
+    group_description: Option<&'static str>,
+    root_description: Option<&'static str>,
+    options: Vec<CommandOption>,
     default_option: bool,
+    permissions: CommandPermissions,
+    cooldown: Option<Cooldown>,
+    scope: Option<ApplicationCommandUpdate>,
+    auto_defer: Option<AutoDefer>,
+}
+
+/// Everything about a [`ModuleCommand`] besides its module and functions.
+#[derive(Default)]
+pub struct ModuleCommandSettings {
+    pub description: Option<&'static str>,
+    /// The description of the group, if this is a grouped subcommand.
+    pub group_description: Option<&'static str>,
+    /// The description of the top-level command, if this is a subcommand.
+    pub root_description: Option<&'static str>,
+    pub options: Vec<CommandOption>,
+    pub default_option: bool,
+    pub permissions: CommandPermissions,
+    pub cooldown: Option<Cooldown>,
+    pub scope: Option<ApplicationCommandUpdate>,
+    pub auto_defer: Option<AutoDefer>,
 }
 
 impl<M: Module> ModuleCommand<M> {
@@ -116,27 +259,79 @@ impl<M: Module> ModuleCommand<M> {
         module: Arc<M>,
         command_function: CommandFunction<M>,
         autocomplete_function: Option<AutocompleteFunction<M>>,
-        options: Vec<(String, OptionBuilder)>,
-        default_option: bool,
+        settings: ModuleCommandSettings,
     ) -> Self {
+        let ModuleCommandSettings {
+            description,
+            group_description,
+            root_description,
+            options,
+            default_option,
+            permissions,
+            cooldown,
+            scope,
+            auto_defer,
+        } = settings;
         Self {
             module,
             command_function,
             autocomplete_function,
+            description,
+            group_description,
+            root_description,
             options,
             default_option,
+            permissions,
+            cooldown,
+            scope,
+            auto_defer,
         }
     }
 }
 
 #[async_trait]
 pub trait Command: Send + Sync {
+    fn module(&self) -> &dyn Module;
+
     fn is_default_option(&self) -> bool;
 
-    fn options(&self) -> Vec<String>;
+    fn permissions(&self) -> CommandPermissions;
+
+    fn cooldown(&self) -> Option<Cooldown>;
+
+    /// Where this command is registered, overriding the scope of its module and the bot.
+    fn scope(&self) -> Option<ApplicationCommandUpdate>;
+
+    /// Overrides the [`AutoDefer`] of the bot for this command.
+    fn auto_defer(&self) -> Option<AutoDefer>;
+
+    /// Used if the [`L10n`] has no description for the default locale, e.g. from a doc comment.
+    fn description(&self) -> Option<&str>;
+
+    /// Like [`Command::description`], but for the subcommand group that contains this command.
+    fn group_description(&self) -> Option<&str> {
+        None
+    }
+
+    /// Like [`Command::description`], but for the root command that contains this subcommand.
+    fn root_description(&self) -> Option<&str> {
+        None
+    }
 
-    fn add_options(&self, l10n: &L10n, command: &mut CreateApplicationCommand);
-    fn add_suboptions(&self, l10n: &L10n, option: &mut CreateApplicationCommandOption);
+    fn options(&self) -> &[CommandOption];
+
+    fn add_options(
+        &self,
+        l10n: &L10n,
+        path: CommandPathRef,
+        command: &mut CreateApplicationCommand,
+    );
+    fn add_suboptions(
+        &self,
+        l10n: &L10n,
+        path: CommandPathRef,
+        option: &mut CreateApplicationCommandOption,
+    );
 
     async fn run(&self, ctx: CommandCtx) -> Result<()>;
     async fn autocomplete(&self, ctx: AutocompleteCtx) -> Result<()>;
@@ -150,60 +345,94 @@ impl Debug for dyn Command {
 
 #[async_trait]
 impl<M: Module> Command for ModuleCommand<M> {
+    fn module(&self) -> &dyn Module {
+        &*self.module
+    }
+
     fn is_default_option(&self) -> bool {
         self.default_option
     }
 
-    fn options(&self) -> Vec<String> {
-        self.options.iter().map(|(name, _)| name.clone()).collect()
+    fn permissions(&self) -> CommandPermissions {
+        self.permissions
     }
 
-    fn add_options(&self, l10n: &L10n, command: &mut CreateApplicationCommand) {
-        for (_, option_builder) in &self.options {
-            command.add_option(option_builder(l10n));
+    fn cooldown(&self) -> Option<Cooldown> {
+        self.cooldown
+    }
+
+    fn scope(&self) -> Option<ApplicationCommandUpdate> {
+        self.scope.clone()
+    }
+
+    fn auto_defer(&self) -> Option<AutoDefer> {
+        self.auto_defer
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.description
+    }
+
+    fn group_description(&self) -> Option<&str> {
+        self.group_description
+    }
+
+    fn root_description(&self) -> Option<&str> {
+        self.root_description
+    }
+
+    fn options(&self) -> &[CommandOption] {
+        &self.options
+    }
+
+    fn add_options(
+        &self,
+        l10n: &L10n,
+        path: CommandPathRef,
+        command: &mut CreateApplicationCommand,
+    ) {
+        for option in &self.options {
+            command.add_option(option.build(l10n, path));
         }
     }
 
-    fn add_suboptions(&self, l10n: &L10n, command: &mut CreateApplicationCommandOption) {
-        for (_, option_builder) in &self.options {
-            command.add_sub_option(option_builder(l10n));
+    fn add_suboptions(
+        &self,
+        l10n: &L10n,
+        path: CommandPathRef,
+        command: &mut CreateApplicationCommandOption,
+    ) {
+        for option in &self.options {
+            command.add_sub_option(option.build(l10n, path));
         }
     }
 
     async fn run(&self, mut ctx: CommandCtx) -> Result<()> {
+        let command_path = CommandPath::resolve(&ctx.interaction.data);
         let options = take(&mut ctx.interaction.data.options);
-        (self.command_function)(self.module.clone(), ctx, options).await
-        // TODO: return a different type of error so e.g. invalid parameters can automatically be
-        // reported nicely like here:
-
-        /*
-        match parameter_from_interaction(&self.command_options, &interaction) {
-            Ok(parameter) => (self.function)(ctx, interaction, parameter).await,
+        match (self.command_function)(self.module.clone(), ctx.bot.http.clone(), options).await {
+            Ok(command_handler) => command_handler(ctx).await,
             Err(invalid_parameters) => {
-                interaction
-                    .create_interaction_response(&ctx.http, |response| {
-                        response.interaction_response_data(|data| {
-                            data.ephemeral(true).embed(|embed| {
-                                embed
-                                    .title(format!(
-                                        "Invalid parameters to `/{}`",
-                                        interaction.data.name
-                                    ))
-                                    .color(colors::css::DANGER)
-                                    .fields(invalid_parameters.iter().map(|invalid_parameter| {
-                                        (
-                                            format!("{}", &invalid_parameter.name),
-                                            format!("{}", invalid_parameter.error),
-                                            false,
-                                        )
-                                    }))
-                            })
+                ctx.respond(|response| {
+                    response.interaction_response_data(|data| {
+                        data.ephemeral(true).embed(|embed| {
+                            embed
+                                .title(format!("Invalid parameters to `/{command_path}`"))
+                                .color(colors::css::DANGER)
+                                .fields(invalid_parameters.0.iter().map(|invalid_parameter| {
+                                    (
+                                        format!("`{}`", invalid_parameter.name),
+                                        invalid_parameter.error.to_string(),
+                                        false,
+                                    )
+                                }))
                         })
                     })
-                    .await
+                })
+                .await?;
+                Ok(())
             }
         }
-        */
     }
 
     async fn autocomplete(&self, mut ctx: AutocompleteCtx) -> Result<()> {
@@ -222,10 +451,16 @@ pub enum CommandMapMergeError {
     DuplicateCommand { path: CommandPath },
     #[error("command `/{path}` cannot also have subcommands")]
     AmbiguousSubcommand { path: CommandPath },
+    #[error("subcommands of `/{name}` must all be registered in the same scope")]
+    ConflictingScopes { name: String },
 }
 
 #[derive(Debug, Default)]
-pub struct CommandMap(HashMap<String, CommandMapEntry>);
+pub struct CommandMap {
+    commands: BTreeMap<String, CommandMapEntry>,
+    user_commands: BTreeMap<String, Box<dyn Command>>,
+    message_commands: BTreeMap<String, Box<dyn Command>>,
+}
 
 #[derive(Debug)]
 pub enum CommandMapEntry {
@@ -234,7 +469,7 @@ pub enum CommandMapEntry {
 }
 
 #[derive(Debug, Default)]
-pub struct SubcommandMap(HashMap<String, SubcommandMapEntry>);
+pub struct SubcommandMap(BTreeMap<String, SubcommandMapEntry>);
 
 #[derive(Debug)]
 pub enum SubcommandMapEntry {
@@ -243,26 +478,50 @@ pub enum SubcommandMapEntry {
 }
 
 #[derive(Debug, Default)]
-pub struct SubcommandGroupMap(HashMap<String, Box<dyn Command>>);
+pub struct SubcommandGroupMap(BTreeMap<String, Box<dyn Command>>);
 
 impl CommandMap {
     pub fn new(
         commands: impl IntoIterator<Item = (CommandPath, Box<dyn Command>)>,
     ) -> Result<Self, CommandMapMergeError> {
-        Self::default().merge(
-            commands
-                .into_iter()
-                .map(|(path, command)| CommandMapEntry::name_and_new(path, command)),
-        )
+        let mut command_map = Self::default();
+        for (path, command) in commands {
+            match path {
+                CommandPath::User { name } => add_context_menu_command(
+                    &mut command_map.user_commands,
+                    name,
+                    command,
+                    |name| CommandPath::User { name },
+                )?,
+                CommandPath::Message { name } => add_context_menu_command(
+                    &mut command_map.message_commands,
+                    name,
+                    command,
+                    |name| CommandPath::Message { name },
+                )?,
+                path => {
+                    let (name, new_entry) = CommandMapEntry::name_and_new(path, command);
+                    command_map.add_entry(name, new_entry)?;
+                }
+            }
+        }
+        Ok(command_map)
     }
 
-    pub(crate) fn merge(
-        mut self,
-        commands: impl IntoIterator<Item = (String, CommandMapEntry)>,
-    ) -> Result<Self, CommandMapMergeError> {
-        for (name, new_entry) in commands {
+    pub(crate) fn merge(mut self, other: CommandMap) -> Result<Self, CommandMapMergeError> {
+        for (name, new_entry) in other.commands {
             self.add_entry(name, new_entry)?;
         }
+        for (name, command) in other.user_commands {
+            add_context_menu_command(&mut self.user_commands, name, command, |name| {
+                CommandPath::User { name }
+            })?;
+        }
+        for (name, command) in other.message_commands {
+            add_context_menu_command(&mut self.message_commands, name, command, |name| {
+                CommandPath::Message { name }
+            })?;
+        }
         Ok(self)
     }
 
@@ -271,7 +530,7 @@ impl CommandMap {
         name: String,
         new_entry: CommandMapEntry,
     ) -> Result<(), CommandMapMergeError> {
-        match self.0.entry(name.clone()) {
+        match self.commands.entry(name.clone()) {
             Entry::Occupied(mut entry) => entry.get_mut().merge(name, new_entry)?,
             Entry::Vacant(entry) => {
                 entry.insert(new_entry);
@@ -280,15 +539,44 @@ impl CommandMap {
         Ok(())
     }
 
+    /// Iterates over all slash commands.
     pub(crate) fn iter(&self) -> impl Iterator<Item = (&String, &CommandMapEntry)> {
-        self.0.iter()
+        self.commands.iter()
+    }
+
+    /// Iterates over all user and message context-menu commands.
+    pub(crate) fn context_menu_commands(
+        &self,
+    ) -> impl Iterator<Item = (CommandType, &String, &dyn Command)> {
+        let user_commands = self
+            .user_commands
+            .iter()
+            .map(|(name, command)| (CommandType::User, name, command.as_ref()));
+        let message_commands = self
+            .message_commands
+            .iter()
+            .map(|(name, command)| (CommandType::Message, name, command.as_ref()));
+        user_commands.chain(message_commands)
     }
 
     pub(crate) fn find_command<'a>(
         &'a self,
         command_path: &CommandPath,
     ) -> Option<&'a dyn Command> {
-        self.0
+        match command_path {
+            CommandPath::User { name } => {
+                return self.user_commands.get(name).map(|command| command.as_ref())
+            }
+            CommandPath::Message { name } => {
+                return self
+                    .message_commands
+                    .get(name)
+                    .map(|command| command.as_ref())
+            }
+            _ => {}
+        }
+
+        self.commands
             .get(command_path.name())
             .and_then(|entry| match (&command_path, entry) {
                 (CommandPath::Command { .. }, CommandMapEntry::Command(command)) => Some(command),
@@ -317,16 +605,54 @@ impl CommandMap {
     }
 }
 
-impl IntoIterator for CommandMap {
-    type Item = (String, CommandMapEntry);
-    type IntoIter = <HashMap<String, CommandMapEntry> as IntoIterator>::IntoIter;
-
-    fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+fn add_context_menu_command(
+    commands: &mut BTreeMap<String, Box<dyn Command>>,
+    name: String,
+    command: Box<dyn Command>,
+    path: impl FnOnce(String) -> CommandPath,
+) -> Result<(), CommandMapMergeError> {
+    match commands.entry(name) {
+        Entry::Occupied(entry) => Err(CommandMapMergeError::DuplicateCommand {
+            path: path(entry.key().clone()),
+        }),
+        Entry::Vacant(entry) => {
+            entry.insert(command);
+            Ok(())
+        }
     }
 }
 
+/// The scope of a single command, falling back to the one of its module.
+pub(crate) fn command_scope(command: &dyn Command) -> Option<ApplicationCommandUpdate> {
+    command.scope().or_else(|| command.module().scope())
+}
+
 impl CommandMapEntry {
+    /// Used if the [`L10n`] has no description for the default locale.
+    pub fn description(&self) -> Option<&str> {
+        match self {
+            CommandMapEntry::Command(command) => command.description(),
+            CommandMapEntry::Subcommands(subcommands) => subcommands.description(),
+        }
+    }
+
+    /// The scope of the root command, which is shared by all of its subcommands.
+    pub(crate) fn scope(&self) -> Option<ApplicationCommandUpdate> {
+        match self {
+            CommandMapEntry::Command(command) => command_scope(command.as_ref()),
+            CommandMapEntry::Subcommands(subcommands) => {
+                subcommands.0.values().next().and_then(|entry| match entry {
+                    SubcommandMapEntry::Subcommand(command) => command_scope(command.as_ref()),
+                    SubcommandMapEntry::Group(commands) => commands
+                        .0
+                        .values()
+                        .next()
+                        .and_then(|command| command_scope(command.as_ref())),
+                })
+            }
+        }
+    }
+
     fn name_and_new(path: CommandPath, command: Box<dyn Command>) -> (String, Self) {
         match path {
             CommandPath::Command { name } => (name, Self::Command(command)),
@@ -352,6 +678,9 @@ impl CommandMapEntry {
                     .into(),
                 )),
             ),
+            CommandPath::User { .. } | CommandPath::Message { .. } => {
+                unreachable!("context-menu commands are stored separately")
+            }
         }
     }
 
@@ -372,6 +701,11 @@ impl CommandMapEntry {
                     path: CommandPath::Command { name },
                 })?
             }
+            (this @ CommandMapEntry::Subcommands(_), new_entry)
+                if this.scope() != new_entry.scope() =>
+            {
+                Err(CommandMapMergeError::ConflictingScopes { name })?
+            }
             (
                 CommandMapEntry::Subcommands(subcommand_map),
                 CommandMapEntry::Subcommands(new_subcommands),
@@ -394,13 +728,26 @@ impl CommandMapEntry {
 
 impl<'a> IntoIterator for &'a SubcommandMap {
     type Item = (&'a String, &'a SubcommandMapEntry);
-    type IntoIter = <&'a HashMap<String, SubcommandMapEntry> as IntoIterator>::IntoIter;
+    type IntoIter = <&'a BTreeMap<String, SubcommandMapEntry> as IntoIterator>::IntoIter;
 
     fn into_iter(self) -> Self::IntoIter {
         self.0.iter()
     }
 }
 
+impl SubcommandMap {
+    /// The first [`Command::root_description`] of the commands in this map.
+    pub fn description(&self) -> Option<&str> {
+        self.0.values().find_map(|entry| match entry {
+            SubcommandMapEntry::Subcommand(command) => command.root_description(),
+            SubcommandMapEntry::Group(group) => group
+                .0
+                .values()
+                .find_map(|command| command.root_description()),
+        })
+    }
+}
+
 impl SubcommandMapEntry {
     fn merge(
         &mut self,
@@ -428,6 +775,13 @@ impl SubcommandMapEntry {
 }
 
 impl SubcommandGroupMap {
+    /// The first [`Command::group_description`] of the commands in this group.
+    pub fn description(&self) -> Option<&str> {
+        self.0
+            .values()
+            .find_map(|command| command.group_description())
+    }
+
     fn merge(
         &mut self,
         name: String,
@@ -453,13 +807,26 @@ impl SubcommandGroupMap {
 
 impl<'a> IntoIterator for &'a SubcommandGroupMap {
     type Item = (&'a String, &'a Box<dyn Command>);
-    type IntoIter = <&'a HashMap<String, Box<dyn Command>> as IntoIterator>::IntoIter;
+    type IntoIter = <&'a BTreeMap<String, Box<dyn Command>> as IntoIterator>::IntoIter;
 
     fn into_iter(self) -> Self::IntoIter {
         self.0.iter()
     }
 }
 
-pub trait CommandProvider {
+#[async_trait]
+pub trait CommandProvider: Send + Sync {
     fn command_map(self: Arc<Self>) -> Result<CommandMap, CommandMapMergeError>;
+
+    /// The default permissions for all commands of this module.
+    fn permissions(&self) -> CommandPermissions {
+        Default::default()
+    }
+
+    /// Wraps every command, component interaction and modal submission of this module.
+    ///
+    /// Runs after the middlewares of the [`Bot`](crate::bot::Bot), see [`Middleware`](crate::middleware::Middleware).
+    async fn middleware(&self, ctx: InteractionCtx, next: Next<'_>) -> Result<()> {
+        next.run(ctx).await
+    }
 }
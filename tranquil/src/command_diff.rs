@@ -0,0 +1,512 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+};
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use serenity::{
+    builder::CreateApplicationCommand,
+    model::application::command::{Command, CommandType},
+};
+
+const COMMAND_KEYS: &[&str] = &[
+    "type",
+    "name",
+    "name_localizations",
+    "description",
+    "description_localizations",
+    "options",
+    "default_member_permissions",
+    "dm_permission",
+];
+
+const OPTION_KEYS: &[&str] = &[
+    "type",
+    "name",
+    "name_localizations",
+    "description",
+    "description_localizations",
+    "required",
+    "choices",
+    "options",
+    "channel_types",
+    "min_value",
+    "max_value",
+    "min_length",
+    "max_length",
+    "autocomplete",
+];
+
+const CHOICE_KEYS: &[&str] = &["name", "name_localizations", "value"];
+
+/// The structural difference between registered and new application commands.
+///
+/// Only the parts of a command that can be set when creating it are compared, so ids, versions
+/// and values that Discord fills in with their defaults do not show up as changes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ApplicationCommandDiff {
+    pub added: Vec<CommandKey>,
+    pub removed: Vec<CommandKey>,
+    pub changed: Vec<(CommandKey, Vec<ValueChange>)>,
+}
+
+/// Identifies a command, since a slash command and a context menu command can share a name.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CommandKey {
+    pub kind: CommandType,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValueChange {
+    pub path: String,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+}
+
+impl ApplicationCommandDiff {
+    pub fn new(current: &[Command], new: &[CreateApplicationCommand]) -> Self {
+        let current = current
+            .iter()
+            .map(|command| {
+                serde_json::to_value(command).expect("command serialization should not fail")
+            })
+            .map(|command| (command_key(&command), normalize_command(command)))
+            .collect::<BTreeMap<_, _>>();
+
+        let new = new
+            .iter()
+            .map(|command| {
+                Value::Object(
+                    command
+                        .0
+                        .iter()
+                        .map(|(&key, value)| (key.to_string(), value.clone()))
+                        .collect(),
+                )
+            })
+            .map(|command| (command_key(&command), normalize_command(command)))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut diff = Self::default();
+
+        for (key, command) in &new {
+            match current.get(key) {
+                Some(current_command) => {
+                    let mut changes = vec![];
+                    diff_values(
+                        String::new(),
+                        Some(current_command),
+                        Some(command),
+                        &mut changes,
+                    );
+                    if !changes.is_empty() {
+                        diff.changed.push((key.clone(), changes));
+                    }
+                }
+                None => diff.added.push(key.clone()),
+            }
+        }
+
+        diff.removed.extend(
+            current
+                .keys()
+                .filter(|key| !new.contains_key(*key))
+                .cloned(),
+        );
+
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl Display for ApplicationCommandDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for key in &self.added {
+            writeln!(f, "+ {key}")?;
+        }
+        for key in &self.removed {
+            writeln!(f, "- {key}")?;
+        }
+        for (key, changes) in &self.changed {
+            writeln!(f, "~ {key}")?;
+            for change in changes {
+                writeln!(f, "    {change}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for ValueChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let describe = |value: &Option<Value>| {
+            value
+                .as_ref()
+                .map_or_else(|| "<none>".to_string(), Value::to_string)
+        };
+        write!(
+            f,
+            "{}: {} → {}",
+            self.path,
+            describe(&self.old),
+            describe(&self.new)
+        )
+    }
+}
+
+impl Display for CommandKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            CommandType::ChatInput => write!(f, "/{}", self.name),
+            CommandType::User => write!(f, "{} (user)", self.name),
+            CommandType::Message => write!(f, "{} (message)", self.name),
+            _ => write!(f, "{} (unknown)", self.name),
+        }
+    }
+}
+
+/// Commands without a type are slash commands.
+fn command_key(command: &Value) -> CommandKey {
+    CommandKey {
+        kind: command
+            .get("type")
+            .and_then(|kind| CommandType::deserialize(kind).ok())
+            .unwrap_or(CommandType::ChatInput),
+        name: command_name(command),
+    }
+}
+
+fn command_name(command: &Value) -> String {
+    command
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn normalize_command(command: Value) -> Value {
+    normalize_object(command, COMMAND_KEYS, |key, value| match key {
+        "type" => value == &Value::from(1),
+        // Context menu commands come back with an empty description.
+        "description" => value == &Value::from(""),
+        "dm_permission" => value == &Value::Bool(true),
+        _ => false,
+    })
+}
+
+fn normalize_option(option: Value) -> Value {
+    normalize_object(option, OPTION_KEYS, |key, value| match key {
+        "required" | "autocomplete" => value == &Value::Bool(false),
+        _ => false,
+    })
+}
+
+fn normalize_choice(choice: Value) -> Value {
+    normalize_object(choice, CHOICE_KEYS, |_, _| false)
+}
+
+/// Only keeps the given keys and removes all values that are equivalent to not being set at all.
+fn normalize_object(
+    value: Value,
+    keys: &[&str],
+    is_default: impl Fn(&str, &Value) -> bool,
+) -> Value {
+    let Value::Object(mut object) = value else {
+        return value;
+    };
+
+    Value::Object(
+        keys.iter()
+            .filter_map(|&key| {
+                let value = match (key, object.remove(key)?) {
+                    ("options", Value::Array(options)) => {
+                        Value::Array(options.into_iter().map(normalize_option).collect())
+                    }
+                    ("choices", Value::Array(choices)) => {
+                        Value::Array(choices.into_iter().map(normalize_choice).collect())
+                    }
+                    // Discord returns whole numbers without a fractional part.
+                    ("min_value" | "max_value", Value::Number(number)) => {
+                        number.as_f64().map_or(Value::Number(number), Value::from)
+                    }
+                    (_, value) => value,
+                };
+                let is_empty = match &value {
+                    Value::Null => true,
+                    Value::Array(array) => array.is_empty(),
+                    Value::Object(object) => object.is_empty(),
+                    _ => false,
+                };
+                (!is_empty && !is_default(key, &value)).then(|| (key.to_string(), value))
+            })
+            .collect::<Map<_, _>>(),
+    )
+}
+
+fn diff_values(
+    path: String,
+    old: Option<&Value>,
+    new: Option<&Value>,
+    changes: &mut Vec<ValueChange>,
+) {
+    match (old, new) {
+        (Some(Value::Object(old)), Some(Value::Object(new))) => {
+            for key in old.keys().chain(new.keys()).collect::<BTreeSet<_>>() {
+                diff_values(join_path(&path, key), old.get(key), new.get(key), changes);
+            }
+        }
+        (Some(Value::Array(old)), Some(Value::Array(new))) if all_named(old) && all_named(new) => {
+            let old_names = old.iter().map(command_name).collect::<Vec<_>>();
+            let new_names = new.iter().map(command_name).collect::<Vec<_>>();
+
+            for name in new_names.iter().chain(&old_names).collect::<BTreeSet<_>>() {
+                let find = |values: &'_ [Value]| {
+                    values
+                        .iter()
+                        .find(|value| &command_name(value) == name)
+                        .cloned()
+                };
+                diff_values(
+                    format!("{path}[{name}]"),
+                    find(old).as_ref(),
+                    find(new).as_ref(),
+                    changes,
+                );
+            }
+
+            let common_old = old_names.iter().filter(|name| new_names.contains(name));
+            let common_new = new_names.iter().filter(|name| old_names.contains(name));
+            if !common_old.eq(common_new) {
+                changes.push(ValueChange {
+                    path: format!("{path} (order)"),
+                    old: Some(old_names.into()),
+                    new: Some(new_names.into()),
+                });
+            }
+        }
+        (old, new) if old != new => changes.push(ValueChange {
+            path,
+            old: old.cloned(),
+            new: new.cloned(),
+        }),
+        _ => {}
+    }
+}
+
+fn all_named(values: &[Value]) -> bool {
+    values
+        .iter()
+        .all(|value| value.get("name").is_some_and(Value::is_string))
+}
+
+fn join_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use serenity::model::application::command::CommandOptionType;
+
+    use super::*;
+
+    /// A command as it is returned by Discord, with all the values it fills in by itself.
+    fn registered(mut command: Value) -> Command {
+        let object = command.as_object_mut().unwrap();
+        object.insert("id".into(), "1".into());
+        object.insert("application_id".into(), "2".into());
+        object.insert("version".into(), "3".into());
+        object.insert("default_permission".into(), true.into());
+        serde_json::from_value(command).unwrap()
+    }
+
+    fn slash(name: &str) -> CommandKey {
+        CommandKey {
+            kind: CommandType::ChatInput,
+            name: name.to_string(),
+        }
+    }
+
+    fn ping() -> CreateApplicationCommand {
+        let mut command = CreateApplicationCommand::default();
+        command
+            .name("ping")
+            .description("Pong!")
+            .create_option(|option| {
+                option
+                    .kind(CommandOptionType::Number)
+                    .name("delay")
+                    .description("In seconds.")
+                    .min_number_value(1.0)
+            });
+        command
+    }
+
+    #[test]
+    fn ignores_default_values() {
+        let current = registered(json!({
+            "type": 1,
+            "name": "ping",
+            "description": "Pong!",
+            "dm_permission": true,
+            "name_localizations": null,
+            "options": [{
+                "type": 10,
+                "name": "delay",
+                "description": "In seconds.",
+                "required": false,
+                "autocomplete": false,
+                "min_value": 1,
+            }],
+        }));
+
+        let diff = ApplicationCommandDiff::new(&[current], &[ping()]);
+        assert!(diff.is_empty(), "{diff}");
+    }
+
+    #[test]
+    fn detects_changed_values() {
+        let current = registered(json!({
+            "type": 1,
+            "name": "ping",
+            "description": "Ping!",
+            "description_localizations": { "de": "Pong!" },
+            "options": [{
+                "type": 10,
+                "name": "delay",
+                "description": "In seconds.",
+                "min_value": 2,
+            }],
+        }));
+
+        let diff = ApplicationCommandDiff::new(&[current], &[ping()]);
+        assert_eq!(
+            diff,
+            ApplicationCommandDiff {
+                changed: vec![(
+                    slash("ping"),
+                    vec![
+                        ValueChange {
+                            path: "description".to_string(),
+                            old: Some(json!("Ping!")),
+                            new: Some(json!("Pong!")),
+                        },
+                        ValueChange {
+                            path: "description_localizations".to_string(),
+                            old: Some(json!({ "de": "Pong!" })),
+                            new: None,
+                        },
+                        ValueChange {
+                            path: "options[delay].min_value".to_string(),
+                            old: Some(json!(2.0)),
+                            new: Some(json!(1.0)),
+                        },
+                    ],
+                )],
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn detects_added_and_removed_commands() {
+        let current = registered(json!({
+            "type": 1,
+            "name": "pong",
+            "description": "Ping!",
+        }));
+
+        let diff = ApplicationCommandDiff::new(&[current], &[ping()]);
+        assert_eq!(diff.added, [slash("ping")]);
+        assert_eq!(diff.removed, [slash("pong")]);
+        assert!(diff.changed.is_empty());
+    }
+
+    #[test]
+    fn detects_reordered_options() {
+        let mut new = CreateApplicationCommand::default();
+        new.name("ping").description("Pong!");
+        for name in ["b", "a"] {
+            new.create_option(|option| {
+                option
+                    .kind(CommandOptionType::String)
+                    .name(name)
+                    .description(name)
+            });
+        }
+        let current = registered(json!({
+            "type": 1,
+            "name": "ping",
+            "description": "Pong!",
+            "options": [
+                { "type": 3, "name": "a", "description": "a" },
+                { "type": 3, "name": "b", "description": "b" },
+            ],
+        }));
+
+        let diff = ApplicationCommandDiff::new(&[current], &[new]);
+        let [(_, changes)] = diff.changed.as_slice() else {
+            panic!("expected a single changed command: {diff:?}");
+        };
+        assert_eq!(
+            changes,
+            &[ValueChange {
+                path: "options (order)".to_string(),
+                old: Some(json!(["a", "b"])),
+                new: Some(json!(["b", "a"])),
+            }]
+        );
+    }
+
+    #[test]
+    fn distinguishes_commands_by_type() {
+        let current = [
+            registered(json!({
+                "type": 1,
+                "name": "info",
+                "description": "Shows info.",
+            })),
+            registered(json!({
+                "type": 2,
+                "name": "info",
+                "description": "",
+            })),
+        ];
+        let mut slash_info = CreateApplicationCommand::default();
+        slash_info.name("info").description("Shows info.");
+        let mut user_info = CreateApplicationCommand::default();
+        user_info.kind(CommandType::User).name("info");
+
+        let diff = ApplicationCommandDiff::new(&current, &[slash_info.clone(), user_info]);
+        assert!(diff.is_empty(), "{diff}");
+
+        let mut message_info = CreateApplicationCommand::default();
+        message_info.kind(CommandType::Message).name("info");
+
+        let diff = ApplicationCommandDiff::new(&current, &[slash_info, message_info]);
+        assert_eq!(
+            diff.added,
+            [CommandKey {
+                kind: CommandType::Message,
+                name: "info".to_string(),
+            }]
+        );
+        assert_eq!(
+            diff.removed,
+            [CommandKey {
+                kind: CommandType::User,
+                name: "info".to_string(),
+            }]
+        );
+        assert!(diff.changed.is_empty());
+    }
+}
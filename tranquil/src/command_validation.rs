@@ -0,0 +1,594 @@
+use std::collections::HashSet;
+
+use serde_json::{Map, Value};
+use serenity::builder::CreateApplicationCommand;
+use thiserror::Error;
+
+use crate::command::CommandPath;
+
+const MAX_NAME_LENGTH: usize = 32;
+const MAX_DESCRIPTION_LENGTH: usize = 100;
+/// Applies to options, choices and subcommands alike.
+const MAX_ITEMS: usize = 25;
+const MAX_GLOBAL_COMMANDS: usize = 100;
+
+// Values of `ApplicationCommandType` and `ApplicationCommandOptionType` in the Discord API.
+const USER_COMMAND: u64 = 2;
+const MESSAGE_COMMAND: u64 = 3;
+const SUBCOMMAND: u64 = 1;
+const SUBCOMMAND_GROUP: u64 = 2;
+
+/// A violation of one of the limits that Discord imposes on application commands.
+#[derive(Debug, Error)]
+pub enum CommandValidationError {
+    /// Slash commands, subcommands and options must match `^[-_\p{L}\p{N}]{1,32}$`.
+    ///
+    /// Context-menu commands can use any characters, but are limited to the same length.
+    #[error("`{path}`: invalid name `{name}`{}", in_locale(.locale))]
+    InvalidName {
+        path: CommandPath,
+        name: String,
+        locale: Option<String>,
+    },
+    #[error("`{path}`: name `{name}`{} must be lowercase", in_locale(.locale))]
+    UppercaseName {
+        path: CommandPath,
+        name: String,
+        locale: Option<String>,
+    },
+    #[error(
+        "`{path}`: description of `{name}`{} must be 1 to 100 characters long",
+        in_locale(.locale)
+    )]
+    InvalidDescription {
+        path: CommandPath,
+        name: String,
+        locale: Option<String>,
+    },
+    #[error("`{path}`: `{name}` has {count} {items}, but at most 25 are allowed")]
+    TooMany {
+        path: CommandPath,
+        name: String,
+        items: &'static str,
+        count: usize,
+    },
+    #[error("`{path}`: required option `{name}` must come before all optional ones")]
+    RequiredAfterOptional { path: CommandPath, name: String },
+    /// Options and subcommands must have unique names, which includes those of flattened options.
+    #[error("`{path}`: `{name}` is defined more than once")]
+    DuplicateName { path: CommandPath, name: String },
+    /// Subcommands can only be nested inside a command or a subcommand group.
+    #[error("`{path}`: subcommand `{name}` is nested too deeply")]
+    NestedTooDeeply { path: CommandPath, name: String },
+    #[error("{count} global slash commands exceed the limit of 100")]
+    TooManyGlobalCommands { count: usize },
+}
+
+fn in_locale(locale: &Option<String>) -> String {
+    locale
+        .as_ref()
+        .map_or_else(String::new, |locale| format!(" for locale `{locale}`"))
+}
+
+#[derive(Debug, Default, Error)]
+pub struct CommandValidationErrors(pub Vec<CommandValidationError>);
+
+impl std::fmt::Display for CommandValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.iter().try_fold((), |_, error| {
+            error.fmt(f)?;
+            writeln!(f)
+        })
+    }
+}
+
+/// Checks the application commands against the limits of Discord, including all translations.
+///
+/// Each command is paired with whether it is registered globally, which counts towards the limit
+/// of global commands. Every violation is reported, not just the first one.
+pub(crate) fn validate_application_commands<'a>(
+    commands: impl IntoIterator<Item = (bool, &'a CreateApplicationCommand)>,
+) -> Result<(), CommandValidationErrors> {
+    let mut errors = vec![];
+    let mut global_commands = 0;
+
+    for (global, command) in commands {
+        let command = Map::from_iter(
+            command
+                .0
+                .iter()
+                .map(|(&key, value)| (key.to_string(), value.clone())),
+        );
+        let name = string_field(&command, "name");
+
+        match command.get("type").and_then(Value::as_u64) {
+            Some(USER_COMMAND) => validate_context_menu_names(
+                &CommandPath::User {
+                    name: name.to_string(),
+                },
+                &command,
+                &mut errors,
+            ),
+            Some(MESSAGE_COMMAND) => validate_context_menu_names(
+                &CommandPath::Message {
+                    name: name.to_string(),
+                },
+                &command,
+                &mut errors,
+            ),
+            _ => {
+                if global {
+                    global_commands += 1;
+                }
+                let path = CommandPath::Command {
+                    name: name.to_string(),
+                };
+                validate_names(&path, &command, &mut errors);
+                validate_descriptions(&path, &command, &mut errors);
+                validate_options(&path, &command, &mut errors);
+            }
+        }
+    }
+
+    if global_commands > MAX_GLOBAL_COMMANDS {
+        errors.push(CommandValidationError::TooManyGlobalCommands {
+            count: global_commands,
+        });
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(CommandValidationErrors(errors))
+    }
+}
+
+fn validate_options(
+    path: &CommandPath,
+    parent: &Map<String, Value>,
+    errors: &mut Vec<CommandValidationError>,
+) {
+    let options = array_field(parent, "options");
+    let items = if options.iter().any(|option| {
+        matches!(
+            option.get("type").and_then(Value::as_u64),
+            Some(SUBCOMMAND | SUBCOMMAND_GROUP)
+        )
+    }) {
+        "subcommands"
+    } else {
+        "options"
+    };
+    validate_count(path, parent, items, options.len(), errors);
+
+    let mut optional = false;
+    let mut names = HashSet::new();
+    for option in options.iter().filter_map(Value::as_object) {
+        let name = string_field(option, "name");
+        if !names.insert(name) {
+            errors.push(CommandValidationError::DuplicateName {
+                path: path.clone(),
+                name: name.to_string(),
+            });
+        }
+        match option.get("type").and_then(Value::as_u64) {
+            Some(SUBCOMMAND | SUBCOMMAND_GROUP) => {
+                let Some(path) = subcommand_path(path, name) else {
+                    errors.push(CommandValidationError::NestedTooDeeply {
+                        path: path.clone(),
+                        name: name.to_string(),
+                    });
+                    continue;
+                };
+                validate_names(&path, option, errors);
+                validate_descriptions(&path, option, errors);
+                validate_options(&path, option, errors);
+            }
+            _ => {
+                validate_names(path, option, errors);
+                validate_descriptions(path, option, errors);
+                validate_count(
+                    path,
+                    option,
+                    "choices",
+                    array_field(option, "choices").len(),
+                    errors,
+                );
+
+                if option.get("required").and_then(Value::as_bool) == Some(true) {
+                    if optional {
+                        errors.push(CommandValidationError::RequiredAfterOptional {
+                            path: path.clone(),
+                            name: name.to_string(),
+                        });
+                    }
+                } else {
+                    optional = true;
+                }
+            }
+        }
+    }
+}
+
+fn validate_count(
+    path: &CommandPath,
+    parent: &Map<String, Value>,
+    items: &'static str,
+    count: usize,
+    errors: &mut Vec<CommandValidationError>,
+) {
+    if count > MAX_ITEMS {
+        errors.push(CommandValidationError::TooMany {
+            path: path.clone(),
+            name: string_field(parent, "name").to_string(),
+            items,
+            count,
+        });
+    }
+}
+
+fn validate_names(
+    path: &CommandPath,
+    object: &Map<String, Value>,
+    errors: &mut Vec<CommandValidationError>,
+) {
+    for (name, locale) in localized(object, "name") {
+        let error = if !is_valid_name(name) {
+            CommandValidationError::InvalidName {
+                path: path.clone(),
+                name: name.to_string(),
+                locale,
+            }
+        } else if name.chars().any(char::is_uppercase) {
+            CommandValidationError::UppercaseName {
+                path: path.clone(),
+                name: name.to_string(),
+                locale,
+            }
+        } else {
+            continue;
+        };
+        errors.push(error);
+    }
+}
+
+fn validate_context_menu_names(
+    path: &CommandPath,
+    object: &Map<String, Value>,
+    errors: &mut Vec<CommandValidationError>,
+) {
+    for (name, locale) in localized(object, "name") {
+        if !(1..=MAX_NAME_LENGTH).contains(&name.chars().count()) {
+            errors.push(CommandValidationError::InvalidName {
+                path: path.clone(),
+                name: name.to_string(),
+                locale,
+            });
+        }
+    }
+}
+
+fn validate_descriptions(
+    path: &CommandPath,
+    object: &Map<String, Value>,
+    errors: &mut Vec<CommandValidationError>,
+) {
+    for (description, locale) in localized(object, "description") {
+        if !(1..=MAX_DESCRIPTION_LENGTH).contains(&description.chars().count()) {
+            errors.push(CommandValidationError::InvalidDescription {
+                path: path.clone(),
+                name: string_field(object, "name").to_string(),
+                locale,
+            });
+        }
+    }
+}
+
+/// Whether the name matches `^[-_\p{L}\p{N}]{1,32}$`, ignoring case.
+fn is_valid_name(name: &str) -> bool {
+    (1..=MAX_NAME_LENGTH).contains(&name.chars().count())
+        && name
+            .chars()
+            .all(|c| c == '-' || c == '_' || c.is_alphanumeric())
+}
+
+/// Yields the value of the given field, followed by all of its translations together with their
+/// locale.
+fn localized<'a>(
+    object: &'a Map<String, Value>,
+    field: &str,
+) -> impl Iterator<Item = (&'a str, Option<String>)> {
+    let localizations = object
+        .get(&format!("{field}_localizations"))
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .map(|(locale, value)| (value.as_str().unwrap_or_default(), Some(locale.clone())));
+    std::iter::once((string_field(object, field), None)).chain(localizations)
+}
+
+/// The path of a subcommand within the given path, unless it cannot be nested any deeper.
+fn subcommand_path(path: &CommandPath, subcommand: &str) -> Option<CommandPath> {
+    match path {
+        CommandPath::Command { name } => Some(CommandPath::Subcommand {
+            name: name.clone(),
+            subcommand: subcommand.to_string(),
+        }),
+        CommandPath::Subcommand {
+            name,
+            subcommand: group,
+        } => Some(CommandPath::Grouped {
+            name: name.clone(),
+            group: group.clone(),
+            subcommand: subcommand.to_string(),
+        }),
+        _ => None,
+    }
+}
+
+fn string_field<'a>(object: &'a Map<String, Value>, field: &str) -> &'a str {
+    object
+        .get(field)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+}
+
+fn array_field<'a>(object: &'a Map<String, Value>, field: &str) -> &'a [Value] {
+    object
+        .get(field)
+        .and_then(Value::as_array)
+        .map_or(&[], Vec::as_slice)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use serenity::model::application::command::{CommandOptionType, CommandType};
+
+    use super::*;
+
+    fn command(name: &str) -> CreateApplicationCommand {
+        let mut command = CreateApplicationCommand::default();
+        command.name(name).description("A command.");
+        command
+    }
+
+    fn validate(commands: &[CreateApplicationCommand]) -> Vec<CommandValidationError> {
+        validate_application_commands(commands.iter().map(|command| (true, command)))
+            .err()
+            .unwrap_or_default()
+            .0
+    }
+
+    fn path(name: &str) -> CommandPath {
+        CommandPath::Command {
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn accepts_valid_commands() {
+        let mut command = command("ping-pong_2");
+        command
+            .name_localized("de", "päng")
+            .create_option(|option| {
+                option
+                    .kind(CommandOptionType::String)
+                    .name("message")
+                    .description("The message.")
+                    .required(true)
+            })
+            .create_option(|option| {
+                option
+                    .kind(CommandOptionType::Integer)
+                    .name("delay")
+                    .description("The delay.")
+            });
+        let mut user_command = CreateApplicationCommand::default();
+        user_command.kind(CommandType::User).name("Show Info");
+
+        assert!(validate(&[command, user_command]).is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_names() {
+        let errors = validate(&[command("with space"), command(&"a".repeat(33)), command("")]);
+        assert!(matches!(
+            errors.as_slice(),
+            [
+                CommandValidationError::InvalidName { name: a, locale: None, .. },
+                CommandValidationError::InvalidName { name: b, .. },
+                CommandValidationError::InvalidName { name: c, .. },
+            ] if a == "with space" && b.len() == 33 && c.is_empty()
+        ));
+    }
+
+    #[test]
+    fn rejects_uppercase_names() {
+        let errors = validate(&[command("Ping")]);
+        assert!(matches!(
+            errors.as_slice(),
+            [CommandValidationError::UppercaseName { path: p, name, locale: None }]
+                if *p == path("Ping") && name == "Ping"
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_localized_names() {
+        let mut command = command("ping");
+        command
+            .name_localized("de", "Ping")
+            .name_localized("fr", "le ping");
+        let errors = validate(&[command]);
+        assert!(matches!(
+            errors.as_slice(),
+            [
+                CommandValidationError::UppercaseName { locale: Some(de), .. },
+                CommandValidationError::InvalidName { locale: Some(fr), .. },
+            ] if de == "de" && fr == "fr"
+        ));
+    }
+
+    #[test]
+    fn allows_any_characters_in_context_menu_names() {
+        let mut command = CreateApplicationCommand::default();
+        command.kind(CommandType::Message).name("Report Message!");
+        assert!(validate(&[command]).is_empty());
+
+        let mut command = CreateApplicationCommand::default();
+        command.kind(CommandType::Message).name("a".repeat(33));
+        assert!(matches!(
+            validate(&[command]).as_slice(),
+            [CommandValidationError::InvalidName { .. }]
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_descriptions() {
+        let mut long = command("long");
+        long.description("a".repeat(101))
+            .description_localized("de", "b".repeat(100));
+        let mut empty = command("empty");
+        empty.description("").description_localized("de", "");
+        let errors = validate(&[long, empty]);
+        assert!(matches!(
+            errors.as_slice(),
+            [
+                CommandValidationError::InvalidDescription { name: a, locale: None, .. },
+                CommandValidationError::InvalidDescription { name: b, locale: None, .. },
+                CommandValidationError::InvalidDescription { name: c, locale: Some(_), .. },
+            ] if a == "long" && b == "empty" && c == "empty"
+        ));
+    }
+
+    #[test]
+    fn rejects_too_many_options() {
+        let mut command = command("ping");
+        for i in 0..26 {
+            command.create_option(|option| {
+                option
+                    .kind(CommandOptionType::String)
+                    .name(format!("option{i}"))
+                    .description("An option.")
+            });
+        }
+        assert!(matches!(
+            validate(&[command]).as_slice(),
+            [CommandValidationError::TooMany {
+                items: "options",
+                count: 26,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn rejects_too_many_choices() {
+        let mut command = command("ping");
+        command.create_option(|option| {
+            option
+                .kind(CommandOptionType::Integer)
+                .name("count")
+                .description("A count.");
+            for i in 0..26 {
+                option.add_int_choice(i.to_string(), i);
+            }
+            option
+        });
+        assert!(matches!(
+            validate(&[command]).as_slice(),
+            [CommandValidationError::TooMany { items: "choices", count: 26, name, .. }]
+                if name == "count"
+        ));
+    }
+
+    #[test]
+    fn rejects_required_after_optional() {
+        let mut command = command("ping");
+        command
+            .create_option(|option| {
+                option
+                    .kind(CommandOptionType::String)
+                    .name("optional")
+                    .description("Optional.")
+            })
+            .create_option(|option| {
+                option
+                    .kind(CommandOptionType::String)
+                    .name("required")
+                    .description("Required.")
+                    .required(true)
+            });
+        assert!(matches!(
+            validate(&[command]).as_slice(),
+            [CommandValidationError::RequiredAfterOptional { name, .. }] if name == "required"
+        ));
+    }
+
+    #[test]
+    fn rejects_duplicate_option_names() {
+        let mut command = command("ping");
+        for _ in 0..2 {
+            command.create_option(|option| {
+                option
+                    .kind(CommandOptionType::String)
+                    .name("message")
+                    .description("The message.")
+            });
+        }
+        assert!(matches!(
+            validate(&[command]).as_slice(),
+            [CommandValidationError::DuplicateName { path: p, name }]
+                if *p == path("ping") && name == "message"
+        ));
+    }
+
+    #[test]
+    fn rejects_too_many_global_commands() {
+        let commands = (0..101)
+            .map(|i| command(&format!("command{i}")))
+            .collect::<Vec<_>>();
+        assert!(matches!(
+            validate(&commands).as_slice(),
+            [CommandValidationError::TooManyGlobalCommands { count: 101 }]
+        ));
+
+        let guild_commands = validate_application_commands(commands.iter().map(|c| (false, c)));
+        assert!(guild_commands.is_ok());
+    }
+
+    #[test]
+    fn validates_subcommands() {
+        let mut command = command("settings");
+        command.0.insert(
+            "options",
+            json!([{
+                "type": 2,
+                "name": "alias",
+                "description": "Aliases.",
+                "options": [{
+                    "type": 1,
+                    "name": "Add",
+                    "description": "Adds an alias.",
+                    "options": [{
+                        "type": 1,
+                        "name": "nested",
+                        "description": "Too deep.",
+                    }],
+                }],
+            }]),
+        );
+        let grouped = CommandPath::Grouped {
+            name: "settings".to_string(),
+            group: "alias".to_string(),
+            subcommand: "Add".to_string(),
+        };
+        assert!(matches!(
+            validate(&[command]).as_slice(),
+            [
+                CommandValidationError::UppercaseName { path: a, .. },
+                CommandValidationError::NestedTooDeeply { path: b, name },
+            ] if *a == grouped && *b == grouped && name == "nested"
+        ));
+    }
+}
@@ -1,20 +1,48 @@
+use std::sync::Arc;
+
 use serenity::{
-    builder::CreateAutocompleteResponse, client::Context,
-    model::application::interaction::autocomplete::AutocompleteInteraction,
+    builder::{CreateAutocompleteResponse, CreateInteractionResponse},
+    client::Context,
+    json::{self, Value},
+    model::application::interaction::{
+        autocomplete::AutocompleteInteraction, InteractionResponseType,
+    },
 };
 
+use crate::{data::Data, initial_response::InitialResponse};
+
 pub struct AutocompleteCtx {
     pub bot: Context,
+    pub data: Arc<Data>,
+    pub(crate) initial_response: Arc<InitialResponse>,
     pub interaction: AutocompleteInteraction,
 }
 
 impl AutocompleteCtx {
+    /// Shared data that was registered using [`Bot::with_data`](crate::bot::Bot::with_data).
+    pub fn data<T: Send + Sync + 'static>(&self) -> &T {
+        self.data.get()
+    }
+
     pub async fn autocomplete<F>(self, f: F) -> serenity::Result<()>
     where
         F: FnOnce(&mut CreateAutocompleteResponse) -> &mut CreateAutocompleteResponse,
     {
-        self.interaction
-            .create_autocomplete_response(&self.bot, f)
+        let mut autocomplete = CreateAutocompleteResponse::default();
+        f(&mut autocomplete);
+        let mut response = CreateInteractionResponse::default();
+        response.kind(InteractionResponseType::Autocomplete);
+        response.0.insert(
+            "data",
+            Value::from(json::hashmap_to_json_map(autocomplete.0)),
+        );
+        self.initial_response
+            .respond(
+                &self.bot.http,
+                self.interaction.id,
+                &self.interaction.token,
+                response,
+            )
             .await
     }
 }
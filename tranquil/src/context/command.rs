@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use serenity::{
     self,
     builder::{
@@ -5,23 +7,32 @@ use serenity::{
     },
     client::Context,
     model::{
-        application::interaction::application_command::ApplicationCommandInteraction,
-        channel::Message, id::MessageId,
+        application::interaction::{
+            application_command::ApplicationCommandInteraction, InteractionResponseType,
+        },
+        channel::Message,
+        id::MessageId,
     },
 };
 
+use crate::{data::Data, initial_response::InitialResponse};
+
 pub struct CommandCtx {
     pub bot: Context,
+    pub data: Arc<Data>,
+    pub(crate) initial_response: Arc<InitialResponse>,
     pub interaction: ApplicationCommandInteraction,
 }
 
 pub struct CommandCtxWithResponse {
     pub bot: Context,
+    pub data: Arc<Data>,
     pub interaction: ApplicationCommandInteraction,
 }
 
 pub struct CommandCtxWithDeletedResponse {
     pub bot: Context,
+    pub data: Arc<Data>,
     pub interaction: ApplicationCommandInteraction,
 }
 
@@ -32,38 +43,78 @@ pub struct CommandCtxFollowups<'a> {
 }
 
 impl CommandCtx {
+    /// Shared data that was registered using [`Bot::with_data`](crate::bot::Bot::with_data).
+    pub fn data<T: Send + Sync + 'static>(&self) -> &T {
+        self.data.get()
+    }
+
+    /// Sends the initial response.
+    ///
+    /// If the interaction was already deferred by [`AutoDefer`](crate::auto_defer::AutoDefer), the
+    /// deferred response is edited instead.
     pub async fn respond<'a, F>(self, f: F) -> serenity::Result<CommandCtxWithResponse>
     where
         for<'b> F:
             FnOnce(&'b mut CreateInteractionResponse<'a>) -> &'b mut CreateInteractionResponse<'a>,
     {
-        self.interaction
-            .create_interaction_response(&self.bot, f)
+        let mut response = CreateInteractionResponse::default();
+        f(&mut response);
+        self.initial_response
+            .respond(
+                &self.bot.http,
+                self.interaction.id,
+                &self.interaction.token,
+                response,
+            )
             .await?;
         Ok(CommandCtxWithResponse {
             bot: self.bot,
+            data: self.data,
             interaction: self.interaction,
         })
     }
 
     pub async fn defer(self) -> serenity::Result<CommandCtxWithResponse> {
-        self.interaction.defer(&self.bot).await?;
+        self.initial_response
+            .defer(
+                &self.bot.http,
+                self.interaction.id,
+                &self.interaction.token,
+                InteractionResponseType::DeferredChannelMessageWithSource,
+                false,
+            )
+            .await?;
         Ok(CommandCtxWithResponse {
             bot: self.bot,
+            data: self.data,
             interaction: self.interaction,
         })
     }
 
     pub async fn defer_ephemeral(self) -> serenity::Result<CommandCtxWithResponse> {
-        self.interaction.defer_ephemeral(&self.bot).await?;
+        self.initial_response
+            .defer(
+                &self.bot.http,
+                self.interaction.id,
+                &self.interaction.token,
+                InteractionResponseType::DeferredChannelMessageWithSource,
+                true,
+            )
+            .await?;
         Ok(CommandCtxWithResponse {
             bot: self.bot,
+            data: self.data,
             interaction: self.interaction,
         })
     }
 }
 
 impl CommandCtxWithResponse {
+    /// Shared data that was registered using [`Bot::with_data`](crate::bot::Bot::with_data).
+    pub fn data<T: Send + Sync + 'static>(&self) -> &T {
+        self.data.get()
+    }
+
     pub async fn get_response(&self) -> serenity::Result<Message> {
         self.interaction.get_interaction_response(&self.bot).await
     }
@@ -84,11 +135,12 @@ impl CommandCtxWithResponse {
             .await?;
         Ok(CommandCtxWithDeletedResponse {
             bot: self.bot,
+            data: self.data,
             interaction: self.interaction,
         })
     }
 
-    pub fn followups(&self) -> CommandCtxFollowups {
+    pub fn followups(&self) -> CommandCtxFollowups<'_> {
         CommandCtxFollowups {
             bot: &self.bot,
             interaction: &self.interaction,
@@ -97,7 +149,12 @@ impl CommandCtxWithResponse {
 }
 
 impl CommandCtxWithDeletedResponse {
-    pub fn followups(&self) -> CommandCtxFollowups {
+    /// Shared data that was registered using [`Bot::with_data`](crate::bot::Bot::with_data).
+    pub fn data<T: Send + Sync + 'static>(&self) -> &T {
+        self.data.get()
+    }
+
+    pub fn followups(&self) -> CommandCtxFollowups<'_> {
         CommandCtxFollowups {
             bot: &self.bot,
             interaction: &self.interaction,
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use serenity::{
     self,
     builder::{
@@ -5,22 +7,30 @@ use serenity::{
     },
     client::Context,
     model::{
-        application::interaction::modal::ModalSubmitInteraction, channel::Message, id::MessageId,
+        application::interaction::{modal::ModalSubmitInteraction, InteractionResponseType},
+        channel::Message,
+        id::MessageId,
     },
 };
 
+use crate::{data::Data, initial_response::InitialResponse};
+
 pub struct ModalCtx {
     pub bot: Context,
+    pub data: Arc<Data>,
+    pub(crate) initial_response: Arc<InitialResponse>,
     pub interaction: ModalSubmitInteraction,
 }
 
 pub struct ModalCtxWithResponse {
     pub bot: Context,
+    pub data: Arc<Data>,
     pub interaction: ModalSubmitInteraction,
 }
 
 pub struct ModalCtxWithDeletedResponse {
     pub bot: Context,
+    pub data: Arc<Data>,
     pub interaction: ModalSubmitInteraction,
 }
 
@@ -31,38 +41,78 @@ pub struct ModalCtxFollowups<'a> {
 }
 
 impl ModalCtx {
+    /// Shared data that was registered using [`Bot::with_data`](crate::bot::Bot::with_data).
+    pub fn data<T: Send + Sync + 'static>(&self) -> &T {
+        self.data.get()
+    }
+
+    /// Sends the initial response.
+    ///
+    /// If the interaction was already deferred by [`AutoDefer`](crate::auto_defer::AutoDefer), the
+    /// deferred response is edited instead.
     pub async fn respond<'a, F>(self, f: F) -> serenity::Result<ModalCtxWithResponse>
     where
         for<'b> F:
             FnOnce(&'b mut CreateInteractionResponse<'a>) -> &'b mut CreateInteractionResponse<'a>,
     {
-        self.interaction
-            .create_interaction_response(&self.bot, f)
+        let mut response = CreateInteractionResponse::default();
+        f(&mut response);
+        self.initial_response
+            .respond(
+                &self.bot.http,
+                self.interaction.id,
+                &self.interaction.token,
+                response,
+            )
             .await?;
         Ok(ModalCtxWithResponse {
             bot: self.bot,
+            data: self.data,
             interaction: self.interaction,
         })
     }
 
     pub async fn defer(self) -> serenity::Result<ModalCtxWithResponse> {
-        self.interaction.defer(&self.bot).await?;
+        self.initial_response
+            .defer(
+                &self.bot.http,
+                self.interaction.id,
+                &self.interaction.token,
+                InteractionResponseType::DeferredUpdateMessage,
+                false,
+            )
+            .await?;
         Ok(ModalCtxWithResponse {
             bot: self.bot,
+            data: self.data,
             interaction: self.interaction,
         })
     }
 
     pub async fn defer_ephemeral(self) -> serenity::Result<ModalCtxWithResponse> {
-        self.interaction.defer_ephemeral(&self.bot).await?;
+        self.initial_response
+            .defer(
+                &self.bot.http,
+                self.interaction.id,
+                &self.interaction.token,
+                InteractionResponseType::DeferredChannelMessageWithSource,
+                true,
+            )
+            .await?;
         Ok(ModalCtxWithResponse {
             bot: self.bot,
+            data: self.data,
             interaction: self.interaction,
         })
     }
 }
 
 impl ModalCtxWithResponse {
+    /// Shared data that was registered using [`Bot::with_data`](crate::bot::Bot::with_data).
+    pub fn data<T: Send + Sync + 'static>(&self) -> &T {
+        self.data.get()
+    }
+
     pub async fn get_response(&self) -> serenity::Result<Message> {
         self.interaction.get_interaction_response(&self.bot).await
     }
@@ -83,11 +133,12 @@ impl ModalCtxWithResponse {
             .await?;
         Ok(ModalCtxWithDeletedResponse {
             bot: self.bot,
+            data: self.data,
             interaction: self.interaction,
         })
     }
 
-    pub fn followups(&self) -> ModalCtxFollowups {
+    pub fn followups(&self) -> ModalCtxFollowups<'_> {
         ModalCtxFollowups {
             bot: &self.bot,
             interaction: &self.interaction,
@@ -96,7 +147,12 @@ impl ModalCtxWithResponse {
 }
 
 impl ModalCtxWithDeletedResponse {
-    pub fn followups(&self) -> ModalCtxFollowups {
+    /// Shared data that was registered using [`Bot::with_data`](crate::bot::Bot::with_data).
+    pub fn data<T: Send + Sync + 'static>(&self) -> &T {
+        self.data.get()
+    }
+
+    pub fn followups(&self) -> ModalCtxFollowups<'_> {
         ModalCtxFollowups {
             bot: &self.bot,
             interaction: &self.interaction,
@@ -0,0 +1,58 @@
+use anyhow::{anyhow, Result};
+use serenity::model::{
+    application::interaction::application_command::{CommandData, ResolvedTarget},
+    channel::Message,
+    guild::PartialMember,
+    user::User,
+};
+
+/// The target of a `#[user_command]`.
+pub trait UserTarget: Sized + Send {
+    fn from_target(user: User, member: Option<PartialMember>) -> Result<Self>;
+}
+
+impl UserTarget for User {
+    fn from_target(user: User, _member: Option<PartialMember>) -> Result<Self> {
+        Ok(user)
+    }
+}
+
+/// Fails if the command was not used inside of a guild.
+impl UserTarget for PartialMember {
+    fn from_target(_user: User, member: Option<PartialMember>) -> Result<Self> {
+        member.ok_or_else(|| anyhow!("target user is not a member"))
+    }
+}
+
+impl UserTarget for (User, Option<PartialMember>) {
+    fn from_target(user: User, member: Option<PartialMember>) -> Result<Self> {
+        Ok((user, member))
+    }
+}
+
+/// The target of a `#[message_command]`.
+pub trait MessageTarget: Sized + Send {
+    fn from_target(message: Message) -> Result<Self>;
+}
+
+impl MessageTarget for Message {
+    fn from_target(message: Message) -> Result<Self> {
+        Ok(message)
+    }
+}
+
+pub fn resolve_user_target<T: UserTarget>(command_data: &CommandData) -> Result<T> {
+    match command_data.target() {
+        Some(ResolvedTarget::User(user, member)) => {
+            T::from_target(user, member.map(|member| *member))
+        }
+        _ => Err(anyhow!("missing target user")),
+    }
+}
+
+pub fn resolve_message_target<T: MessageTarget>(command_data: &CommandData) -> Result<T> {
+    match command_data.target() {
+        Some(ResolvedTarget::Message(message)) => T::from_target(*message),
+        _ => Err(anyhow!("missing target message")),
+    }
+}
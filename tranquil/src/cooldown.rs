@@ -0,0 +1,193 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// Who shares the same cooldown.
+///
+/// In direct messages, [`CooldownScope::Guild`] falls back to the channel.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CooldownScope {
+    #[default]
+    User,
+    Guild,
+    Channel,
+    Global,
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Cooldown {
+    pub duration: Duration,
+    pub scope: CooldownScope,
+}
+
+impl Cooldown {
+    pub const fn new(duration: Duration, scope: CooldownScope) -> Self {
+        Self { duration, scope }
+    }
+
+    pub const fn per_user(duration: Duration) -> Self {
+        Self::new(duration, CooldownScope::User)
+    }
+
+    pub const fn per_guild(duration: Duration) -> Self {
+        Self::new(duration, CooldownScope::Guild)
+    }
+
+    pub const fn per_channel(duration: Duration) -> Self {
+        Self::new(duration, CooldownScope::Channel)
+    }
+
+    pub const fn global(duration: Duration) -> Self {
+        Self::new(duration, CooldownScope::Global)
+    }
+}
+
+/// Identifies a single cooldown bucket.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CooldownKey {
+    /// The command path or the uuid of the component.
+    pub name: String,
+    pub scope: CooldownScope,
+    /// The id of the user, guild or channel, or zero for [`CooldownScope::Global`].
+    pub id: u64,
+}
+
+/// Keeps track of cooldowns, e.g. in memory or in a shared database.
+#[async_trait]
+pub trait CooldownBackend: Send + Sync {
+    /// Starts the cooldown unless it is still running, in which case the remaining time is returned.
+    async fn try_start(&self, key: CooldownKey, duration: Duration) -> Result<Option<Duration>>;
+}
+
+/// Keeps track of cooldowns in memory, which means they are reset when the bot restarts.
+#[derive(Debug, Default)]
+pub struct InMemoryCooldownBackend(Mutex<Cooldowns>);
+
+impl InMemoryCooldownBackend {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+#[async_trait]
+impl CooldownBackend for InMemoryCooldownBackend {
+    async fn try_start(&self, key: CooldownKey, duration: Duration) -> Result<Option<Duration>> {
+        Ok(self
+            .0
+            .lock()
+            .expect("cooldowns should not be poisoned")
+            .try_start(key, duration, Instant::now()))
+    }
+}
+
+/// The number of cooldowns that can be stored before expired ones are removed for the first time.
+const MIN_PRUNE_LEN: usize = 64;
+
+/// The end of each cooldown, some of which might have expired already.
+///
+/// Expired cooldowns are removed once the number of cooldowns doubled since the last removal, so
+/// that each call only takes constant time on average.
+#[derive(Debug)]
+struct Cooldowns {
+    ends: HashMap<CooldownKey, Instant>,
+    prune_len: usize,
+}
+
+impl Default for Cooldowns {
+    fn default() -> Self {
+        Self {
+            ends: Default::default(),
+            prune_len: MIN_PRUNE_LEN,
+        }
+    }
+}
+
+impl Cooldowns {
+    fn try_start(
+        &mut self,
+        key: CooldownKey,
+        duration: Duration,
+        now: Instant,
+    ) -> Option<Duration> {
+        if let Some(&end) = self.ends.get(&key) {
+            if end > now {
+                return Some(end - now);
+            }
+        }
+        self.ends.insert(key, now + duration);
+        if self.ends.len() >= self.prune_len {
+            self.ends.retain(|_, end| *end > now);
+            self.prune_len = (self.ends.len() * 2).max(MIN_PRUNE_LEN);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DURATION: Duration = Duration::from_secs(10);
+
+    fn key(id: u64) -> CooldownKey {
+        CooldownKey {
+            name: "ping".to_string(),
+            scope: CooldownScope::User,
+            id,
+        }
+    }
+
+    #[test]
+    fn starts_cooldowns_per_key() {
+        let mut cooldowns = Cooldowns::default();
+        let now = Instant::now();
+        assert_eq!(cooldowns.try_start(key(1), DURATION, now), None);
+        assert_eq!(cooldowns.try_start(key(2), DURATION, now), None);
+    }
+
+    #[test]
+    fn returns_remaining_time_of_running_cooldowns() {
+        let mut cooldowns = Cooldowns::default();
+        let now = Instant::now();
+        cooldowns.try_start(key(1), DURATION, now);
+        let later = now + Duration::from_secs(3);
+        assert_eq!(
+            cooldowns.try_start(key(1), DURATION, later),
+            Some(Duration::from_secs(7))
+        );
+        // A running cooldown is not restarted.
+        assert_eq!(
+            cooldowns.try_start(key(1), DURATION, later),
+            Some(Duration::from_secs(7))
+        );
+    }
+
+    #[test]
+    fn restarts_expired_cooldowns() {
+        let mut cooldowns = Cooldowns::default();
+        let now = Instant::now();
+        cooldowns.try_start(key(1), DURATION, now);
+        let later = now + DURATION;
+        assert_eq!(cooldowns.try_start(key(1), DURATION, later), None);
+        assert_eq!(cooldowns.try_start(key(1), DURATION, later), Some(DURATION));
+    }
+
+    #[test]
+    fn removes_expired_cooldowns() {
+        let mut cooldowns = Cooldowns::default();
+        let now = Instant::now();
+        for id in 0..MIN_PRUNE_LEN as u64 - 1 {
+            cooldowns.try_start(key(id), DURATION, now);
+        }
+        assert_eq!(cooldowns.ends.len(), MIN_PRUNE_LEN - 1);
+
+        cooldowns.try_start(key(u64::MAX), DURATION, now + DURATION);
+        assert_eq!(cooldowns.ends.len(), 1);
+        assert_eq!(cooldowns.prune_len, MIN_PRUNE_LEN);
+    }
+}
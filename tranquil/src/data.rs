@@ -0,0 +1,36 @@
+use std::{
+    any::{type_name, Any, TypeId},
+    collections::HashMap,
+};
+
+/// A type map of values that are shared between all modules.
+///
+/// Values are registered using [`Bot::with_data`](crate::bot::Bot::with_data) and can be accessed
+/// from every context using e.g. [`CommandCtx::data`](crate::context::command::CommandCtx::data).
+#[derive(Debug, Default)]
+pub struct Data(HashMap<TypeId, Box<dyn Any + Send + Sync>>);
+
+impl Data {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Inserts a value, replacing the previous value of the same type.
+    pub fn insert<T: Send + Sync + 'static>(&mut self, value: T) {
+        self.0.insert(TypeId::of::<T>(), Box::new(value));
+    }
+
+    pub fn try_get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.0
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref())
+    }
+
+    /// # Panics
+    ///
+    /// Panics if no value of type `T` was registered.
+    pub fn get<T: Send + Sync + 'static>(&self) -> &T {
+        self.try_get()
+            .unwrap_or_else(|| panic!("no data of type {} registered", type_name::<T>()))
+    }
+}
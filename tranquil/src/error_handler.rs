@@ -0,0 +1,134 @@
+use std::fmt::Display;
+
+use async_trait::async_trait;
+use serenity::{
+    builder::CreateInteractionResponse, client::Context,
+    model::application::interaction::Interaction, utils::colours as colors,
+};
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::{command::CommandPath, initial_response::InitialResponse};
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum InteractionKind {
+    Command,
+    Autocomplete,
+    Component,
+    Modal,
+}
+
+/// Identifies what an interaction was targeting.
+///
+/// Components and modals only carry the uuid of their custom_id, which is [`None`] if the
+/// custom_id could not be parsed.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum InteractionOrigin {
+    Command(CommandPath),
+    Autocomplete(CommandPath),
+    Component(Option<Uuid>),
+    Modal(Option<Uuid>),
+}
+
+impl InteractionOrigin {
+    pub fn kind(&self) -> InteractionKind {
+        match self {
+            InteractionOrigin::Command(_) => InteractionKind::Command,
+            InteractionOrigin::Autocomplete(_) => InteractionKind::Autocomplete,
+            InteractionOrigin::Component(_) => InteractionKind::Component,
+            InteractionOrigin::Modal(_) => InteractionKind::Modal,
+        }
+    }
+}
+
+impl Display for InteractionOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InteractionOrigin::Command(command_path) if command_path.is_slash() => {
+                write!(f, "command `/{command_path}`")
+            }
+            InteractionOrigin::Command(command_path) => write!(f, "command `{command_path}`"),
+            InteractionOrigin::Autocomplete(command_path) => {
+                write!(f, "autocomplete of `/{command_path}`")
+            }
+            InteractionOrigin::Component(Some(uuid)) => write!(f, "component {uuid}"),
+            InteractionOrigin::Component(None) => write!(f, "unknown component"),
+            InteractionOrigin::Modal(Some(uuid)) => write!(f, "modal {uuid}"),
+            InteractionOrigin::Modal(None) => write!(f, "unknown modal"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InteractionError {
+    pub error: anyhow::Error,
+    pub origin: InteractionOrigin,
+    pub interaction: Interaction,
+}
+
+impl InteractionError {
+    /// Sends the response if nothing was sent yet, or edits the deferred response.
+    ///
+    /// Returns `false` without sending anything if a response was already sent. Whether the
+    /// interaction was responded to is only known while [`ErrorHandler::handle_error`] runs.
+    pub async fn respond_or_edit<'a, F>(&self, bot: &Context, f: F) -> serenity::Result<bool>
+    where
+        for<'b> F:
+            FnOnce(&'b mut CreateInteractionResponse<'a>) -> &'b mut CreateInteractionResponse<'a>,
+    {
+        let mut response = CreateInteractionResponse::default();
+        f(&mut response);
+        InitialResponse::current()
+            .respond_or_edit(
+                &bot.http,
+                self.interaction.id(),
+                self.interaction.token(),
+                response,
+            )
+            .await
+    }
+}
+
+#[async_trait]
+pub trait ErrorHandler: Send + Sync {
+    async fn handle_error(&self, bot: Context, error: InteractionError);
+}
+
+/// Logs the error and replies with an ephemeral error message.
+///
+/// If the interaction was deferred, the deferred response is edited instead. Nothing is sent if a
+/// response was already sent.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultErrorHandler;
+
+#[async_trait]
+impl ErrorHandler for DefaultErrorHandler {
+    async fn handle_error(&self, bot: Context, error: InteractionError) {
+        error!("error during {}: {:?}", error.origin, error.error);
+
+        if let Interaction::Ping(_) | Interaction::Autocomplete(_) = error.interaction {
+            return;
+        }
+
+        match error.respond_or_edit(&bot, error_response).await {
+            Ok(true) => {}
+            Ok(false) => debug!("not reporting error, since a response was already sent"),
+            Err(error) => error!("failed to report error: {error}"),
+        }
+    }
+}
+
+fn error_response<'a, 'b>(
+    response: &'b mut CreateInteractionResponse<'a>,
+) -> &'b mut CreateInteractionResponse<'a> {
+    response.interaction_response_data(|data| {
+        data.embed(|embed| {
+            embed.color(colors::css::DANGER).field(
+                ":x: Something went wrong",
+                "An error occurred while handling this interaction.",
+                false,
+            )
+        })
+        .ephemeral(true)
+    })
+}
@@ -0,0 +1,481 @@
+use std::{
+    convert::Infallible,
+    net::{SocketAddr, TcpListener},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{Context as _, Result};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use hyper::{
+    body::{Bytes, HttpBody},
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use serenity::{
+    client::{bridge::gateway::ShardMessenger, Context, EventHandler},
+    futures::channel::mpsc,
+    http::Http,
+    model::application::interaction::{Interaction, InteractionResponseType},
+    prelude::{RwLock, TypeMap},
+};
+use tracing::{error, info, warn};
+
+use crate::{
+    bot::Bot,
+    initial_response::InitialResponse,
+    utils::{json_response, status_response},
+};
+
+/// The maximum size of an interaction request, which is well above anything Discord sends.
+const MAX_BODY_SIZE: usize = 64 * 1024;
+
+/// How long to wait for the initial response before deferring the interaction.
+///
+/// Discord gives up after 3 seconds, which leaves some time for the reply to arrive.
+const RESPONSE_DEADLINE: Duration = Duration::from_millis(2500);
+
+/// Parses the hex encoded public key of a Discord application.
+pub(crate) fn parse_public_key(public_key: &str) -> Result<VerifyingKey> {
+    let bytes = hex::decode(public_key.trim()).context("public key is not valid hex")?;
+    let bytes = bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("public key should be 32 bytes long"))?;
+    VerifyingKey::from_bytes(&bytes).context("invalid public key")
+}
+
+/// Checks the `X-Signature-Ed25519` header of a request that was sent by Discord.
+pub fn verify_signature(
+    public_key: &VerifyingKey,
+    signature: &str,
+    timestamp: &str,
+    body: &[u8],
+) -> bool {
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    let Ok(signature) = Signature::from_slice(&signature) else {
+        return false;
+    };
+    public_key
+        .verify(&[timestamp.as_bytes(), body].concat(), &signature)
+        .is_ok()
+}
+
+/// Receives interactions via HTTP and returns their initial response in the HTTP reply.
+///
+/// All other requests, like followups or edits of the initial response, are sent to Discord as
+/// usual.
+pub(crate) struct HttpInteractions {
+    listener: TcpListener,
+    public_key: VerifyingKey,
+}
+
+impl HttpInteractions {
+    pub(crate) fn bind(addr: SocketAddr, public_key: VerifyingKey) -> Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            public_key,
+        })
+    }
+
+    pub(crate) async fn serve(self, bot: Arc<Bot>, http: Arc<Http>) -> Result<()> {
+        let state = Arc::new(State {
+            bot,
+            http,
+            public_key: self.public_key,
+        });
+
+        let addr = self.listener.local_addr()?;
+        let endpoint = Server::from_tcp(self.listener)?.serve(make_service_fn(move |_| {
+            let state = state.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    state.clone().handle_interaction_request(request)
+                }))
+            }
+        }));
+
+        info!("Listening for interactions on {addr}");
+        endpoint.await?;
+        Ok(())
+    }
+}
+
+struct State {
+    bot: Arc<Bot>,
+    http: Arc<Http>,
+    public_key: VerifyingKey,
+}
+
+impl State {
+    async fn handle_interaction_request(
+        self: Arc<Self>,
+        request: Request<Body>,
+    ) -> Result<Response<Body>, Infallible> {
+        if request.method() != Method::POST {
+            return Ok(status_response(StatusCode::METHOD_NOT_ALLOWED));
+        }
+
+        let header = |name| {
+            request
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(ToOwned::to_owned)
+        };
+        let (Some(signature), Some(timestamp)) = (
+            header("X-Signature-Ed25519"),
+            header("X-Signature-Timestamp"),
+        ) else {
+            return Ok(status_response(StatusCode::UNAUTHORIZED));
+        };
+
+        let body = match read_body(request.into_body()).await {
+            Ok(body) => body,
+            Err(status) => return Ok(status_response(status)),
+        };
+
+        if !verify_signature(&self.public_key, &signature, &timestamp, &body) {
+            return Ok(status_response(StatusCode::UNAUTHORIZED));
+        }
+
+        let interaction = match serde_json::from_slice::<Interaction>(&body) {
+            Ok(interaction) => interaction,
+            Err(error) => {
+                error!("invalid interaction: {error}");
+                return Ok(status_response(StatusCode::BAD_REQUEST));
+            }
+        };
+
+        if let Interaction::Ping(_) = interaction {
+            return Ok(json_response(Bytes::from_static(br#"{"type":1}"#)));
+        }
+
+        Ok(self.respond(interaction).await)
+    }
+
+    /// Runs the interaction and waits until it either responds or finishes without a response.
+    ///
+    /// If neither happens before [`RESPONSE_DEADLINE`], the interaction is deferred instead. The
+    /// handler keeps running in the background after the reply, e.g. to send followups.
+    async fn respond(self: Arc<Self>, interaction: Interaction) -> Response<Body> {
+        let (initial_response, mut receiver) = InitialResponse::local();
+        let initial_response = Arc::new(initial_response);
+        let interaction_id = interaction.id();
+        let token = interaction.token().to_owned();
+        let deferred_kind = deferred_kind(&interaction);
+
+        let mut handler = tokio::spawn({
+            let state = self.clone();
+            let initial_response = initial_response.clone();
+            async move {
+                let (shard, _) = mpsc::unbounded();
+                let bot = Context {
+                    data: Arc::new(RwLock::new(TypeMap::new())),
+                    shard: ShardMessenger::new(shard),
+                    shard_id: 0,
+                    http: state.http.clone(),
+                };
+                initial_response
+                    .scope(state.bot.interaction_create(bot, interaction))
+                    .await;
+            }
+        });
+
+        let response = tokio::select! {
+            response = &mut receiver => response.ok(),
+            _ = &mut handler => {
+                let response = receiver.try_recv().ok();
+                if response.is_none() {
+                    error!("interaction finished without a response");
+                }
+                response
+            }
+            _ = tokio::time::sleep(RESPONSE_DEADLINE) => match deferred_kind {
+                Some(kind) => {
+                    warn!("interaction did not respond in time, deferring it");
+                    // Does nothing if the handler responded in the meantime, in which case its
+                    // response is received instead.
+                    let deferred = initial_response
+                        .auto_defer(&self.http, interaction_id, &token, kind, false)
+                        .await;
+                    if let Err(error) = deferred {
+                        error!("failed to defer interaction: {error}");
+                    }
+                    receiver.try_recv().ok()
+                }
+                None => {
+                    error!("interaction did not respond in time");
+                    None
+                }
+            },
+        };
+
+        match response {
+            Some(response) => json_response(Bytes::from(response.to_string())),
+            None => status_response(StatusCode::INTERNAL_SERVER_ERROR),
+        }
+    }
+}
+
+/// How an interaction is deferred, or [`None`] if it cannot be deferred.
+fn deferred_kind(interaction: &Interaction) -> Option<InteractionResponseType> {
+    match interaction {
+        Interaction::ApplicationCommand(_) => {
+            Some(InteractionResponseType::DeferredChannelMessageWithSource)
+        }
+        Interaction::MessageComponent(_) => Some(InteractionResponseType::DeferredUpdateMessage),
+        Interaction::ModalSubmit(modal) if modal.message.is_some() => {
+            Some(InteractionResponseType::DeferredUpdateMessage)
+        }
+        Interaction::ModalSubmit(_) => {
+            Some(InteractionResponseType::DeferredChannelMessageWithSource)
+        }
+        Interaction::Ping(_) | Interaction::Autocomplete(_) => None,
+    }
+}
+
+/// Reads the whole body, but rejects it as soon as it is known to exceed [`MAX_BODY_SIZE`].
+async fn read_body(mut body: Body) -> Result<Vec<u8>, StatusCode> {
+    if body.size_hint().lower() > MAX_BODY_SIZE as u64 {
+        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+    let mut bytes = vec![];
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|_| StatusCode::BAD_REQUEST)?;
+        if bytes.len() + chunk.len() > MAX_BODY_SIZE {
+            return Err(StatusCode::PAYLOAD_TOO_LARGE);
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Signer, SigningKey};
+    use hyper::body::to_bytes;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::{
+        context::command::CommandCtx,
+        macros::{command_provider, slash},
+        module::Module,
+    };
+
+    #[derive(Module)]
+    struct SlowModule;
+
+    #[command_provider]
+    impl SlowModule {
+        #[slash]
+        async fn slow(&self, _ctx: CommandCtx) -> anyhow::Result<()> {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            Ok(())
+        }
+    }
+
+    const TIMESTAMP: &str = "1700000000";
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[7; 32])
+    }
+
+    fn sign(timestamp: &str, body: &[u8]) -> String {
+        let message = [timestamp.as_bytes(), body].concat();
+        hex::encode(signing_key().sign(&message).to_bytes())
+    }
+
+    fn state() -> Arc<State> {
+        Arc::new(State {
+            bot: Arc::new(Bot::new()),
+            http: Arc::new(Http::new("test")),
+            public_key: signing_key().verifying_key(),
+        })
+    }
+
+    fn command(name: &str) -> Value {
+        json!({
+            "id": "1",
+            "application_id": "2",
+            "type": 2,
+            "data": { "id": "3", "name": name, "type": 1 },
+            "channel_id": "4",
+            "user": { "id": "5", "username": "user", "discriminator": "0000", "avatar": null },
+            "token": "token",
+            "version": 1,
+            "locale": "en-US",
+        })
+    }
+
+    fn request(signature: &str, body: Value) -> Request<Body> {
+        Request::post("/interactions")
+            .header("X-Signature-Ed25519", signature)
+            .header("X-Signature-Timestamp", TIMESTAMP)
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    async fn send(request: Request<Body>) -> (StatusCode, Value) {
+        let response = state().handle_interaction_request(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    fn ping() -> Value {
+        json!({
+            "id": "1",
+            "application_id": "2",
+            "type": 1,
+            "token": "token",
+            "version": 1,
+        })
+    }
+
+    #[test]
+    fn accepts_valid_signature() {
+        let public_key = signing_key().verifying_key();
+        let signature = sign(TIMESTAMP, b"body");
+        assert!(verify_signature(
+            &public_key,
+            &signature,
+            TIMESTAMP,
+            b"body"
+        ));
+    }
+
+    #[test]
+    fn rejects_tampered_requests() {
+        let public_key = signing_key().verifying_key();
+        let signature = sign(TIMESTAMP, b"body");
+        assert!(!verify_signature(
+            &public_key,
+            &signature,
+            TIMESTAMP,
+            b"other body"
+        ));
+        assert!(!verify_signature(
+            &public_key,
+            &signature,
+            "1700000001",
+            b"body"
+        ));
+
+        let other_key = SigningKey::from_bytes(&[8; 32]).verifying_key();
+        assert!(!verify_signature(
+            &other_key, &signature, TIMESTAMP, b"body"
+        ));
+    }
+
+    #[test]
+    fn rejects_malformed_signatures() {
+        let public_key = signing_key().verifying_key();
+        assert!(!verify_signature(
+            &public_key,
+            "not hex",
+            TIMESTAMP,
+            b"body"
+        ));
+        assert!(!verify_signature(&public_key, "abcd", TIMESTAMP, b"body"));
+    }
+
+    #[test]
+    fn parses_public_key() {
+        let public_key = hex::encode(signing_key().verifying_key().to_bytes());
+        assert_eq!(
+            parse_public_key(&public_key).unwrap(),
+            signing_key().verifying_key(),
+        );
+        assert!(parse_public_key("abcd").is_err());
+    }
+
+    #[tokio::test]
+    async fn responds_to_ping_with_pong() {
+        let ping = ping();
+        let signature = sign(TIMESTAMP, ping.to_string().as_bytes());
+        let (status, body) = send(request(&signature, ping)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, json!({ "type": 1 }));
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_signature() {
+        let signature = sign(TIMESTAMP, b"something else");
+        let (status, _) = send(request(&signature, ping())).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn rejects_missing_signature() {
+        let request = Request::post("/interactions")
+            .body(Body::from(ping().to_string()))
+            .unwrap();
+        let (status, _) = send(request).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn rejects_oversized_bodies() {
+        let body = vec![b' '; MAX_BODY_SIZE + 1];
+        let request = Request::post("/interactions")
+            .header("X-Signature-Ed25519", sign(TIMESTAMP, &body))
+            .header("X-Signature-Timestamp", TIMESTAMP)
+            .body(Body::from(body))
+            .unwrap();
+        let (status, _) = send(request).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn rejects_oversized_streamed_bodies() {
+        let (mut sender, body) = Body::channel();
+        tokio::spawn(async move {
+            let chunk = Bytes::from(vec![b' '; 1024]);
+            while sender.send_data(chunk.clone()).await.is_ok() {}
+        });
+        let request = Request::post("/interactions")
+            .header("X-Signature-Ed25519", sign(TIMESTAMP, b""))
+            .header("X-Signature-Timestamp", TIMESTAMP)
+            .body(body)
+            .unwrap();
+        let (status, _) = send(request).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn returns_initial_response_in_reply() {
+        let command = command("unknown");
+        let signature = sign(TIMESTAMP, command.to_string().as_bytes());
+        let (status, body) = send(request(&signature, command)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["type"], 4);
+        assert_eq!(body["data"]["flags"], 64);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn defers_slow_interactions_at_the_deadline() {
+        let mut bot = Bot::new().register(SlowModule);
+        bot.load().await.unwrap();
+        let state = Arc::new(State {
+            bot: Arc::new(bot),
+            http: Arc::new(Http::new("test")),
+            public_key: signing_key().verifying_key(),
+        });
+
+        let command = command("slow");
+        let signature = sign(TIMESTAMP, command.to_string().as_bytes());
+        let response = state
+            .handle_interaction_request(request(&signature, command))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body()).await.unwrap();
+        let body = serde_json::from_slice::<Value>(&body).unwrap();
+        assert_eq!(body, json!({ "type": 5 }));
+    }
+}
@@ -0,0 +1,250 @@
+use std::{future::Future, sync::Arc};
+
+use serenity::{
+    builder::CreateInteractionResponse,
+    http::Http,
+    json::{self, Value},
+    model::{application::interaction::InteractionResponseType, id::InteractionId},
+};
+use tokio::sync::{oneshot, Mutex};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum InitialResponseState {
+    #[default]
+    Pending,
+    /// Deferred using either `DeferredChannelMessageWithSource` or `DeferredUpdateMessage`.
+    Deferred(InteractionResponseType),
+    Sent,
+}
+
+tokio::task_local! {
+    static CURRENT: Arc<InitialResponse>;
+}
+
+/// Sends the initial response of an interaction and keeps track of whether it was deferred.
+///
+/// Interactions that were received via HTTP pass their initial response to a local channel, so
+/// that it can be returned in the HTTP reply instead of being sent to Discord.
+#[derive(Debug, Default)]
+pub(crate) struct InitialResponse(Mutex<(InitialResponseState, Option<oneshot::Sender<Value>>)>);
+
+impl InitialResponse {
+    /// Creates an initial response that is passed to the returned receiver.
+    #[cfg(any(feature = "http-interactions", feature = "testing"))]
+    pub(crate) fn local() -> (Self, oneshot::Receiver<Value>) {
+        let (sender, receiver) = oneshot::channel();
+        (
+            Self(Mutex::new((InitialResponseState::Pending, Some(sender)))),
+            receiver,
+        )
+    }
+
+    /// The initial response of the interaction that is handled by the current task.
+    pub(crate) fn current() -> Arc<Self> {
+        CURRENT.try_with(Arc::clone).unwrap_or_default()
+    }
+
+    /// Makes this the [`current`](Self::current) initial response while running `future`.
+    pub(crate) async fn scope<T>(self: Arc<Self>, future: impl Future<Output = T>) -> T {
+        CURRENT.scope(self, future).await
+    }
+
+    /// Sends the response or, if the interaction was deferred in the meantime, edits it instead.
+    pub(crate) async fn respond(
+        &self,
+        http: &Http,
+        interaction_id: InteractionId,
+        token: &str,
+        response: CreateInteractionResponse<'_>,
+    ) -> serenity::Result<()> {
+        let mut guard = self.0.lock().await;
+        let (state, local) = &mut *guard;
+        if let InitialResponseState::Deferred(deferred) = *state {
+            return edit_deferred_response(http, token, deferred, response).await;
+        }
+        create_response(http, local, interaction_id, token, response).await?;
+        *state = InitialResponseState::Sent;
+        Ok(())
+    }
+
+    /// Sends the response if nothing was sent yet, or edits the deferred response.
+    ///
+    /// Unlike [`respond`](Self::respond), the deferred response is edited regardless of how the
+    /// interaction was deferred. Returns `false` if a response was already sent.
+    pub(crate) async fn respond_or_edit(
+        &self,
+        http: &Http,
+        interaction_id: InteractionId,
+        token: &str,
+        response: CreateInteractionResponse<'_>,
+    ) -> serenity::Result<bool> {
+        let mut guard = self.0.lock().await;
+        let (state, local) = &mut *guard;
+        match *state {
+            InitialResponseState::Pending => {
+                create_response(http, local, interaction_id, token, response).await?;
+                *state = InitialResponseState::Sent;
+            }
+            InitialResponseState::Deferred(_) => {
+                edit_original_response(http, token, response).await?;
+            }
+            InitialResponseState::Sent => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Defers the interaction, unless it was already deferred.
+    pub(crate) async fn defer(
+        &self,
+        http: &Http,
+        interaction_id: InteractionId,
+        token: &str,
+        kind: InteractionResponseType,
+        ephemeral: bool,
+    ) -> serenity::Result<()> {
+        let mut guard = self.0.lock().await;
+        let (state, local) = &mut *guard;
+        if let InitialResponseState::Deferred(_) = state {
+            return Ok(());
+        }
+        let response = deferred_response(kind, ephemeral);
+        create_response(http, local, interaction_id, token, response).await?;
+        *state = InitialResponseState::Deferred(kind);
+        Ok(())
+    }
+
+    /// Defers the interaction, but only if nothing was sent yet.
+    pub(crate) async fn auto_defer(
+        &self,
+        http: &Http,
+        interaction_id: InteractionId,
+        token: &str,
+        kind: InteractionResponseType,
+        ephemeral: bool,
+    ) -> serenity::Result<()> {
+        let mut guard = self.0.lock().await;
+        let (state, local) = &mut *guard;
+        if *state == InitialResponseState::Pending {
+            let response = deferred_response(kind, ephemeral);
+            create_response(http, local, interaction_id, token, response).await?;
+            *state = InitialResponseState::Deferred(kind);
+        }
+        Ok(())
+    }
+}
+
+/// A response that defers the interaction, to be followed up by an edit later on.
+fn deferred_response(
+    kind: InteractionResponseType,
+    ephemeral: bool,
+) -> CreateInteractionResponse<'static> {
+    let mut response = CreateInteractionResponse::default();
+    response.kind(kind);
+    if ephemeral {
+        response.interaction_response_data(|data| data.ephemeral(true));
+    }
+    response
+}
+
+async fn create_response(
+    http: &Http,
+    local: &mut Option<oneshot::Sender<Value>>,
+    interaction_id: InteractionId,
+    token: &str,
+    response: CreateInteractionResponse<'_>,
+) -> serenity::Result<()> {
+    let CreateInteractionResponse(response, files) = response;
+    let response = Value::from(json::hashmap_to_json_map(response));
+    match local.take() {
+        Some(_) if !files.is_empty() => Err(serenity::Error::Other(
+            "cannot attach files to the initial response of an HTTP interaction",
+        )),
+        Some(local) => local.send(response).map_err(|_| {
+            serenity::Error::Other("the HTTP interaction no longer awaits a response")
+        }),
+        None if files.is_empty() => {
+            http.create_interaction_response(interaction_id.0, token, &response)
+                .await
+        }
+        None => {
+            http.create_interaction_response_with_files(interaction_id.0, token, &response, files)
+                .await
+        }
+    }
+}
+
+async fn edit_deferred_response(
+    http: &Http,
+    token: &str,
+    deferred: InteractionResponseType,
+    response: CreateInteractionResponse<'_>,
+) -> serenity::Result<()> {
+    let is_kind = |kind| response.0.get("type") == Some(&Value::from(kind as u8));
+    if is_kind(InteractionResponseType::Modal) {
+        return Err(serenity::Error::Other(
+            "cannot open a modal after the interaction was deferred",
+        ));
+    }
+    // Editing the deferred response edits the message of a component when it was deferred using
+    // `DeferredUpdateMessage` and the new message otherwise.
+    match deferred {
+        InteractionResponseType::DeferredUpdateMessage
+            if is_kind(InteractionResponseType::ChannelMessageWithSource) =>
+        {
+            // The message of the component stays as is and the new message becomes a followup.
+            return create_followup(http, token, response).await;
+        }
+        InteractionResponseType::DeferredChannelMessageWithSource
+            if is_kind(InteractionResponseType::UpdateMessage) =>
+        {
+            return Err(serenity::Error::Other(
+                "cannot update the message after the interaction was deferred with a new message",
+            ));
+        }
+        _ => {}
+    }
+    edit_original_response(http, token, response).await
+}
+
+async fn create_followup(
+    http: &Http,
+    token: &str,
+    mut response: CreateInteractionResponse<'_>,
+) -> serenity::Result<()> {
+    let data = response
+        .0
+        .remove("data")
+        .unwrap_or_else(|| Value::Object(Default::default()));
+    if response.1.is_empty() {
+        http.create_followup_message(token, &data).await?;
+    } else {
+        http.create_followup_message_with_files(token, &data, response.1)
+            .await?;
+    }
+    Ok(())
+}
+
+async fn edit_original_response(
+    http: &Http,
+    token: &str,
+    mut response: CreateInteractionResponse<'_>,
+) -> serenity::Result<()> {
+    if !response.1.is_empty() {
+        return Err(serenity::Error::Other(
+            "cannot attach files after the interaction was deferred",
+        ));
+    }
+
+    let mut data = response
+        .0
+        .remove("data")
+        .unwrap_or_else(|| Value::Object(Default::default()));
+    // Whether the response is ephemeral was already decided when it was deferred.
+    if let Value::Object(data) = &mut data {
+        data.remove("flags");
+    }
+
+    http.edit_original_interaction_response(token, &data)
+        .await?;
+    Ok(())
+}
@@ -3,11 +3,12 @@ use async_trait::async_trait;
 use serde::{de::DeserializeOwned, Serialize};
 use uuid::Uuid;
 
-use crate::{context::component::ComponentCtx, module::Module};
+use crate::{context::component::ComponentCtx, cooldown::Cooldown, module::Module};
 
 #[async_trait]
 pub trait Interact: Serialize + DeserializeOwned {
     const UUID: Uuid;
+    const COOLDOWN: Option<Cooldown> = None;
 
     type Module: Module;
 
@@ -21,6 +22,16 @@ macro_rules! handle_interactions {
             &[ $( <$Interact>::UUID, )* ]
         }
 
+        fn interaction_cooldown(
+            &self,
+            uuid: $crate::uuid::Uuid,
+        ) -> ::std::option::Option<$crate::cooldown::Cooldown> {
+            match uuid {
+                $( <$Interact>::UUID => <$Interact as $crate::interaction::Interact>::COOLDOWN, )*
+                _ => ::std::option::Option::None,
+            }
+        }
+
         #[allow(unused_variables)]
         fn interact<'life0, 'life1, 'async_trait>(
             &'life0 self,
@@ -48,7 +59,9 @@ macro_rules! handle_interactions {
                             ctx,
                         ).await
                     } )*
-                    _ => ::std::panic!("module does not handle interactions with uuid {uuid}"),
+                    _ => ::std::result::Result::Err($crate::anyhow::anyhow!(
+                        "module does not handle interactions with uuid {uuid}"
+                    )),
                 }
             })
         }
@@ -5,7 +5,10 @@ use std::{
 
 use enumset::{EnumSet, EnumSetType};
 use serde::{Deserialize, Serialize};
-use serenity::builder::{CreateApplicationCommand, CreateApplicationCommandOption};
+use serenity::{
+    builder::{CreateApplicationCommand, CreateApplicationCommandOption},
+    model::application::command::CommandType,
+};
 use thiserror::Error;
 
 use crate::{
@@ -19,7 +22,13 @@ pub struct L10n {
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     commands: BTreeMap<String, CommandL10n>,
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    user_commands: BTreeMap<String, Translations>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    message_commands: BTreeMap<String, Translations>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     choices: BTreeMap<String, ChoiceL10n>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    messages: BTreeMap<String, Translations>,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
@@ -45,15 +54,15 @@ struct OptionL10n {
 }
 
 impl OptionL10n {
-    fn stubs(locales: EnumSet<Locale>) -> Self {
+    fn stubs(locales: EnumSet<Locale>, description: Option<&str>) -> Self {
         let mut l10n = Self::default();
-        l10n.fill_stubs(locales);
+        l10n.fill_stubs(locales, description);
         l10n
     }
 
-    fn fill_stubs(&mut self, locales: EnumSet<Locale>) {
+    fn fill_stubs(&mut self, locales: EnumSet<Locale>, description: Option<&str>) {
         self.name.fill_stubs(locales);
-        self.description.fill_stubs(locales);
+        self.description.fill_stubs_with(locales, description);
     }
 }
 
@@ -155,8 +164,14 @@ pub enum L10nLoadError {
     Parse(#[from] serde_yaml::Error),
     #[error("duplicate command {command}")]
     DuplicateCommand { command: String },
+    #[error("duplicate user command {command}")]
+    DuplicateUserCommand { command: String },
+    #[error("duplicate message command {command}")]
+    DuplicateMessageCommand { command: String },
     #[error("duplicate choice {choice}")]
     DuplicateChoice { choice: String },
+    #[error("duplicate message {message}")]
+    DuplicateMessage { message: String },
 }
 
 #[derive(Debug, Default, Error)]
@@ -264,6 +279,30 @@ impl L10n {
                     }
                 }),
         );
+        errors.0.extend(other.user_commands.into_iter().filter_map(
+            |(command_name, translation)| match self.user_commands.entry(command_name) {
+                Entry::Vacant(entry) => {
+                    entry.insert(translation);
+                    None
+                }
+                Entry::Occupied(entry) => Some(L10nLoadError::DuplicateUserCommand {
+                    command: entry.key().clone(),
+                }),
+            },
+        ));
+        errors
+            .0
+            .extend(other.message_commands.into_iter().filter_map(
+                |(command_name, translation)| match self.message_commands.entry(command_name) {
+                    Entry::Vacant(entry) => {
+                        entry.insert(translation);
+                        None
+                    }
+                    Entry::Occupied(entry) => Some(L10nLoadError::DuplicateMessageCommand {
+                        command: entry.key().clone(),
+                    }),
+                },
+            ));
         errors.0.extend(
             other
                 .choices
@@ -280,6 +319,22 @@ impl L10n {
                     },
                 ),
         );
+        errors.0.extend(
+            other
+                .messages
+                .into_iter()
+                .filter_map(|(message_name, translation)| {
+                    match self.messages.entry(message_name) {
+                        Entry::Vacant(entry) => {
+                            entry.insert(translation);
+                            None
+                        }
+                        Entry::Occupied(entry) => Some(L10nLoadError::DuplicateMessage {
+                            message: entry.key().clone(),
+                        }),
+                    }
+                }),
+        );
         if errors.0.is_empty() {
             Ok(())
         } else {
@@ -287,6 +342,18 @@ impl L10n {
         }
     }
 
+    /// Translates a message that the bot sends by itself, like the one for cooldowns.
+    ///
+    /// Falls back to the default locale if there is no translation for the given locale.
+    pub fn message(&self, name: &str, locale: &str) -> Option<&str> {
+        let translations = &self.messages.get(name)?.0;
+        Locale::try_from(locale)
+            .ok()
+            .and_then(|locale| translations.get(&locale))
+            .or_else(|| translations.get(&Locale::default()))
+            .map(AsRef::as_ref)
+    }
+
     pub fn command_stubs(
         command_map: &CommandMap,
         locales: EnumSet<Locale>,
@@ -307,6 +374,14 @@ impl L10n {
                 .or_default()
                 .fill_stubs_from_command_map_entry(command_map_entry, locales)?;
         }
+        for (kind, name, _) in command_map.context_menu_commands() {
+            if let Some(context_menu_commands) = self.context_menu_commands_mut(kind) {
+                context_menu_commands
+                    .entry(name.clone())
+                    .or_default()
+                    .fill_stubs(locales);
+            }
+        }
         Ok(())
     }
 
@@ -324,6 +399,25 @@ impl L10n {
         l10n
     }
 
+    fn context_menu_commands(&self, kind: CommandType) -> Option<&BTreeMap<String, Translations>> {
+        match kind {
+            CommandType::User => Some(&self.user_commands),
+            CommandType::Message => Some(&self.message_commands),
+            _ => None,
+        }
+    }
+
+    fn context_menu_commands_mut(
+        &mut self,
+        kind: CommandType,
+    ) -> Option<&mut BTreeMap<String, Translations>> {
+        match kind {
+            CommandType::User => Some(&mut self.user_commands),
+            CommandType::Message => Some(&mut self.message_commands),
+            _ => None,
+        }
+    }
+
     fn resolve_command_name(&self, name: &str) -> Option<&CommandL10n> {
         self.commands.get(name)
     }
@@ -358,10 +452,18 @@ impl L10n {
         })
     }
 
-    pub(crate) fn describe_command(&self, name: &str, command: &mut CreateApplicationCommand) {
+    /// The `default_description` is used if there is no translation for the default locale.
+    pub(crate) fn describe_command(
+        &self,
+        name: &str,
+        default_description: Option<&str>,
+        command: &mut CreateApplicationCommand,
+    ) {
         let translations = self.resolve_command_name(name);
-        let description =
-            translation_or_default(translations.map(|translations| &translations.description));
+        let description = translation_or_default(
+            translations.map(|translations| &translations.description),
+            default_description,
+        );
 
         command.name(name).description(description);
 
@@ -375,14 +477,36 @@ impl L10n {
         }
     }
 
+    /// Context-menu commands only have a name, which is also what is shown in the menu.
+    pub(crate) fn describe_context_menu_command(
+        &self,
+        kind: CommandType,
+        name: &str,
+        command: &mut CreateApplicationCommand,
+    ) {
+        command.kind(kind).name(name);
+
+        let translations = self
+            .context_menu_commands(kind)
+            .and_then(|commands| commands.get(name));
+        if let Some(translations) = translations {
+            for (locale, translation) in &translations.0 {
+                command.name_localized(locale, translation);
+            }
+        }
+    }
+
     pub(crate) fn describe_subcommand(
         &self,
         path: CommandPathRef,
+        default_description: Option<&str>,
         option: &mut CreateApplicationCommandOption,
     ) {
         let translations = self.resolve_command_path(path);
-        let description =
-            translation_or_default(translations.map(|translations| &translations.description));
+        let description = translation_or_default(
+            translations.map(|translations| &translations.description),
+            default_description,
+        );
 
         option.name(path.subcommand()).description(description);
 
@@ -400,11 +524,14 @@ impl L10n {
         &self,
         path: CommandPathRef,
         name: &str,
+        default_description: Option<&str>,
         option: &mut CreateApplicationCommandOption,
     ) {
         let translations = self.resolve_command_option(path, name);
-        let description =
-            translation_or_default(translations.map(|translations| &translations.description));
+        let description = translation_or_default(
+            translations.map(|translations| &translations.description),
+            default_description,
+        );
 
         option.name(name).description(description);
 
@@ -453,13 +580,14 @@ impl CommandL10n {
         command: &CommandMapEntry,
         locales: EnumSet<Locale>,
     ) -> Result<(), L10nStubError> {
-        self.name.fill_stubs(locales);
-        self.description.fill_stubs(locales);
         match command {
             CommandMapEntry::Command(command) => {
                 self.fill_stubs_from_command(command, locales)?;
             }
             CommandMapEntry::Subcommands(subcommands) => {
+                self.name.fill_stubs(locales);
+                self.description
+                    .fill_stubs_with(locales, subcommands.description());
                 for (name, subcommand) in subcommands {
                     self.subcommands
                         .entry(name.clone())
@@ -476,13 +604,14 @@ impl CommandL10n {
         subcommand: &SubcommandMapEntry,
         locales: EnumSet<Locale>,
     ) -> Result<(), L10nStubError> {
-        self.name.fill_stubs(locales);
-        self.description.fill_stubs(locales);
         match subcommand {
             SubcommandMapEntry::Subcommand(command) => {
                 self.fill_stubs_from_command(command, locales)?;
             }
             SubcommandMapEntry::Group(group) => {
+                self.name.fill_stubs(locales);
+                self.description
+                    .fill_stubs_with(locales, group.description());
                 for (name, command) in group {
                     self.subcommands
                         .entry(name.clone())
@@ -502,22 +631,27 @@ impl CommandL10n {
         let command = command.as_ref();
 
         self.name.fill_stubs(locales);
-        self.description.fill_stubs(locales);
+        self.description
+            .fill_stubs_with(locales, command.description());
 
-        let new_options = command.options();
-        let mut new_options = new_options.iter();
+        let mut new_options = command.options().iter();
 
-        for ((current_name, current_option), name) in zip(self.options.iter_mut(), &mut new_options)
+        for ((current_name, current_option), option) in
+            zip(self.options.iter_mut(), &mut new_options)
         {
-            if name != current_name {
+            if option.name != *current_name {
                 Err(L10nStubError::MismatchedOptions)?;
             } else {
-                current_option.fill_stubs(locales);
+                current_option.fill_stubs(locales, option.description);
             }
         }
 
-        self.options
-            .extend(new_options.map(|option| (option.clone(), OptionL10n::stubs(locales))));
+        self.options.extend(new_options.map(|option| {
+            (
+                option.name.clone(),
+                OptionL10n::stubs(locales, option.description),
+            )
+        }));
 
         Ok(())
     }
@@ -535,14 +669,30 @@ impl Translations {
     }
 
     fn fill_stubs(&mut self, locales: EnumSet<Locale>) {
+        self.fill_stubs_with(locales, None);
+    }
+
+    /// Fills missing translations with "TODO".
+    ///
+    /// The default locale is filled with the given text instead, if any, since it is taken from
+    /// the doc comments, which are already written in that locale.
+    fn fill_stubs_with(&mut self, locales: EnumSet<Locale>, text: Option<&str>) {
         for locale in locales {
-            self.0.entry(locale).or_insert_with(|| "TODO".to_string());
+            self.0.entry(locale).or_insert_with(|| {
+                text.filter(|_| locale == Locale::default())
+                    .unwrap_or("TODO")
+                    .to_string()
+            });
         }
     }
 }
 
-fn translation_or_default(translations: Option<&Translations>) -> &str {
+fn translation_or_default<'a>(
+    translations: Option<&'a Translations>,
+    default: Option<&'a str>,
+) -> &'a str {
     translations
         .and_then(|translations| translations.0.get(&Locale::default()).map(AsRef::as_ref))
+        .or(default)
         .unwrap_or("n/a")
 }
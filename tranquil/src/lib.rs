@@ -1,19 +1,37 @@
+pub mod auto_defer;
 pub mod autocomplete;
 pub mod bot;
 pub mod button;
 pub mod command;
+pub mod command_diff;
+pub mod command_validation;
 pub mod context;
+pub mod context_menu;
+pub mod cooldown;
 pub mod custom_id;
+pub mod data;
+pub mod error_handler;
+#[cfg(feature = "http-interactions")]
+pub mod http_interactions;
+pub mod initial_response;
 pub mod interaction;
 pub mod l10n;
+pub mod listener;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod middleware;
 pub mod modal;
 pub mod module;
 pub mod resolve;
 pub mod select_menu;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod utils;
 
 pub mod macros {
-    pub use tranquil_macros::{autocompleter, command_provider, slash};
+    pub use tranquil_macros::{
+        autocompleter, command_provider, message_command, slash, user_command,
+    };
 }
 
 extern crate self as tranquil;
@@ -0,0 +1,206 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serenity::{
+    client::Context,
+    model::{event::*, gateway::GatewayIntents},
+};
+
+use crate::module::Module;
+
+/// A gateway event that modules can listen to using [`Listener`].
+pub trait GatewayEvent: Send + Sync {
+    /// The intents that are required to receive this event.
+    const INTENTS: GatewayIntents;
+
+    fn from_event(event: &Event) -> Option<&Self>;
+}
+
+macro_rules! impl_gateway_event {
+    { $( $Variant:ident($Event:ty) => $( $intent:ident )|*, )* } => { $(
+        impl GatewayEvent for $Event {
+            const INTENTS: GatewayIntents =
+                GatewayIntents::empty() $( .union(GatewayIntents::$intent) )*;
+
+            fn from_event(event: &Event) -> Option<&Self> {
+                match event {
+                    Event::$Variant(event) => Some(event),
+                    _ => None,
+                }
+            }
+        }
+    )* };
+}
+
+impl_gateway_event! {
+    AutoModerationRuleCreate(AutoModerationRuleCreateEvent) => AUTO_MODERATION_CONFIGURATION,
+    AutoModerationRuleUpdate(AutoModerationRuleUpdateEvent) => AUTO_MODERATION_CONFIGURATION,
+    AutoModerationRuleDelete(AutoModerationRuleDeleteEvent) => AUTO_MODERATION_CONFIGURATION,
+    AutoModerationActionExecution(AutoModerationActionExecutionEvent) => AUTO_MODERATION_EXECUTION,
+    ChannelCreate(ChannelCreateEvent) => GUILDS,
+    ChannelDelete(ChannelDeleteEvent) => GUILDS,
+    ChannelPinsUpdate(ChannelPinsUpdateEvent) => GUILDS | DIRECT_MESSAGES,
+    ChannelUpdate(ChannelUpdateEvent) => GUILDS,
+    GuildBanAdd(GuildBanAddEvent) => GUILD_BANS,
+    GuildBanRemove(GuildBanRemoveEvent) => GUILD_BANS,
+    GuildEmojisUpdate(GuildEmojisUpdateEvent) => GUILD_EMOJIS_AND_STICKERS,
+    GuildIntegrationsUpdate(GuildIntegrationsUpdateEvent) => GUILD_INTEGRATIONS,
+    GuildMemberAdd(GuildMemberAddEvent) => GUILD_MEMBERS,
+    GuildMemberRemove(GuildMemberRemoveEvent) => GUILD_MEMBERS,
+    GuildMemberUpdate(GuildMemberUpdateEvent) => GUILD_MEMBERS,
+    GuildMembersChunk(GuildMembersChunkEvent) => ,
+    GuildRoleCreate(GuildRoleCreateEvent) => GUILDS,
+    GuildRoleDelete(GuildRoleDeleteEvent) => GUILDS,
+    GuildRoleUpdate(GuildRoleUpdateEvent) => GUILDS,
+    GuildStickersUpdate(GuildStickersUpdateEvent) => GUILD_EMOJIS_AND_STICKERS,
+    GuildUpdate(GuildUpdateEvent) => GUILDS,
+    InviteCreate(InviteCreateEvent) => GUILD_INVITES,
+    InviteDelete(InviteDeleteEvent) => GUILD_INVITES,
+    MessageCreate(MessageCreateEvent) => GUILD_MESSAGES | DIRECT_MESSAGES,
+    MessageDelete(MessageDeleteEvent) => GUILD_MESSAGES | DIRECT_MESSAGES,
+    MessageDeleteBulk(MessageDeleteBulkEvent) => GUILD_MESSAGES,
+    MessageUpdate(MessageUpdateEvent) => GUILD_MESSAGES | DIRECT_MESSAGES,
+    PresenceUpdate(PresenceUpdateEvent) => GUILD_PRESENCES,
+    ReactionAdd(ReactionAddEvent) => GUILD_MESSAGE_REACTIONS | DIRECT_MESSAGE_REACTIONS,
+    ReactionRemove(ReactionRemoveEvent) => GUILD_MESSAGE_REACTIONS | DIRECT_MESSAGE_REACTIONS,
+    ReactionRemoveAll(ReactionRemoveAllEvent) => GUILD_MESSAGE_REACTIONS | DIRECT_MESSAGE_REACTIONS,
+    TypingStart(TypingStartEvent) => GUILD_MESSAGE_TYPING | DIRECT_MESSAGE_TYPING,
+    UserUpdate(UserUpdateEvent) => ,
+    VoiceStateUpdate(VoiceStateUpdateEvent) => GUILD_VOICE_STATES,
+    WebhookUpdate(WebhookUpdateEvent) => GUILD_WEBHOOKS,
+    IntegrationCreate(IntegrationCreateEvent) => GUILD_INTEGRATIONS,
+    IntegrationUpdate(IntegrationUpdateEvent) => GUILD_INTEGRATIONS,
+    IntegrationDelete(IntegrationDeleteEvent) => GUILD_INTEGRATIONS,
+    StageInstanceCreate(StageInstanceCreateEvent) => GUILDS,
+    StageInstanceUpdate(StageInstanceUpdateEvent) => GUILDS,
+    StageInstanceDelete(StageInstanceDeleteEvent) => GUILDS,
+    ThreadCreate(ThreadCreateEvent) => GUILDS,
+    ThreadUpdate(ThreadUpdateEvent) => GUILDS,
+    ThreadDelete(ThreadDeleteEvent) => GUILDS,
+    ThreadListSync(ThreadListSyncEvent) => GUILDS,
+    ThreadMemberUpdate(ThreadMemberUpdateEvent) => GUILDS,
+    ThreadMembersUpdate(ThreadMembersUpdateEvent) => GUILDS | GUILD_MEMBERS,
+    GuildScheduledEventCreate(GuildScheduledEventCreateEvent) => GUILD_SCHEDULED_EVENTS,
+    GuildScheduledEventUpdate(GuildScheduledEventUpdateEvent) => GUILD_SCHEDULED_EVENTS,
+    GuildScheduledEventDelete(GuildScheduledEventDeleteEvent) => GUILD_SCHEDULED_EVENTS,
+    GuildScheduledEventUserAdd(GuildScheduledEventUserAddEvent) => GUILD_SCHEDULED_EVENTS,
+    GuildScheduledEventUserRemove(GuildScheduledEventUserRemoveEvent) => GUILD_SCHEDULED_EVENTS,
+}
+
+/// Handles a single type of gateway event for a module.
+///
+/// Listeners are hooked up using [`handle_events!`](crate::handle_events) inside the module's
+/// [`Module`] impl, which also requests the required intents.
+#[async_trait]
+pub trait Listener<E: GatewayEvent>: Module {
+    async fn listen(&self, bot: &Context, event: &E) -> Result<()>;
+}
+
+#[macro_export]
+macro_rules! handle_events {
+    [ $( $Event:ty ),* $( , )? ] => {
+        fn event_intents(&self) -> $crate::serenity::model::gateway::GatewayIntents {
+            $crate::serenity::model::gateway::GatewayIntents::empty()
+                $( | <$Event as $crate::listener::GatewayEvent>::INTENTS )*
+        }
+
+        #[allow(unused_variables)]
+        fn handle_event<'life0, 'life1, 'life2, 'async_trait>(
+            &'life0 self,
+            bot: &'life1 $crate::serenity::client::Context,
+            event: &'life2 $crate::serenity::model::event::Event,
+        ) -> ::std::pin::Pin<
+            ::std::boxed::Box<
+                dyn ::std::future::Future<Output = $crate::anyhow::Result<()>>
+                    + ::std::marker::Send
+                    + 'async_trait
+            >
+        >
+        where
+            'life0: 'async_trait,
+            'life1: 'async_trait,
+            'life2: 'async_trait,
+            Self: 'async_trait,
+        {
+            ::std::boxed::Box::pin(async move {
+                // Each event has a single type, so at most one listener can match.
+                $( if let ::std::option::Option::Some(event) = <$Event as $crate::listener::GatewayEvent>::from_event(event) {
+                    return $crate::listener::Listener::<$Event>::listen(self, bot, event).await;
+                } )*
+                ::std::result::Result::Ok(())
+            })
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use anyhow::bail;
+    use serenity::{
+        client::bridge::gateway::ShardMessenger,
+        futures::channel::mpsc,
+        http::Http,
+        prelude::{RwLock, TypeMap},
+    };
+
+    use super::*;
+    use crate::macros::command_provider;
+
+    #[derive(Default)]
+    struct CountingModule {
+        typing: AtomicUsize,
+        deleted: AtomicUsize,
+    }
+
+    impl Module for CountingModule {
+        handle_events![TypingStartEvent, MessageDeleteEvent];
+    }
+
+    #[command_provider]
+    impl CountingModule {}
+
+    #[async_trait]
+    impl Listener<TypingStartEvent> for CountingModule {
+        async fn listen(&self, _bot: &Context, _event: &TypingStartEvent) -> Result<()> {
+            self.typing.fetch_add(1, Ordering::Relaxed);
+            bail!("typing listener failed")
+        }
+    }
+
+    #[async_trait]
+    impl Listener<MessageDeleteEvent> for CountingModule {
+        async fn listen(&self, _bot: &Context, _event: &MessageDeleteEvent) -> Result<()> {
+            self.deleted.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn runs_only_the_matching_listener() {
+        let (shard, _) = mpsc::unbounded();
+        let bot = Context {
+            data: Arc::new(RwLock::new(TypeMap::new())),
+            shard: ShardMessenger::new(shard),
+            shard_id: 0,
+            http: Arc::new(Http::new("test")),
+        };
+        let event = Event::TypingStart(
+            serde_json::from_value(serde_json::json!({
+                "channel_id": "1",
+                "user_id": "1",
+                "timestamp": 0,
+            }))
+            .unwrap(),
+        );
+
+        let module = CountingModule::default();
+        let error = module.handle_event(&bot, &event).await.unwrap_err();
+        assert_eq!(error.to_string(), "typing listener failed");
+        assert_eq!(module.typing.load(Ordering::Relaxed), 1);
+        assert_eq!(module.deleted.load(Ordering::Relaxed), 0);
+    }
+}
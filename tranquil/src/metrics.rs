@@ -0,0 +1,131 @@
+use std::{convert::Infallible, net::TcpListener, sync::Arc, time::Duration};
+
+use anyhow::Result;
+use hyper::{
+    header::CONTENT_TYPE,
+    service::{make_service_fn, service_fn},
+    Body, Response, Server,
+};
+use prometheus::{HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder};
+use tracing::{error, info};
+
+use crate::{
+    error_handler::{InteractionKind, InteractionOrigin},
+    utils::status_response,
+};
+
+/// Counts and times every interaction, labelled by its kind, origin and outcome.
+///
+/// The origin is the command path for commands and autocompletes and the uuid for components and
+/// modals.
+#[derive(Clone, Debug)]
+pub struct Metrics {
+    registry: Registry,
+    interactions: IntCounterVec,
+    durations: HistogramVec,
+}
+
+const LABELS: &[&str] = &["kind", "origin", "outcome"];
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::with_registry(Registry::new()).expect("metrics should register in a new registry")
+    }
+
+    /// Registers the metrics in an existing registry, e.g. to expose them together with others.
+    pub fn with_registry(registry: Registry) -> prometheus::Result<Self> {
+        let interactions = IntCounterVec::new(
+            Opts::new(
+                "tranquil_interactions_total",
+                "Number of handled interactions.",
+            ),
+            LABELS,
+        )?;
+        let durations = HistogramVec::new(
+            HistogramOpts::new(
+                "tranquil_interaction_duration_seconds",
+                "Time it took to handle interactions.",
+            ),
+            LABELS,
+        )?;
+        registry.register(Box::new(interactions.clone()))?;
+        registry.register(Box::new(durations.clone()))?;
+        Ok(Self {
+            registry,
+            interactions,
+            durations,
+        })
+    }
+
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Encodes all metrics of the registry in the Prometheus text format.
+    pub fn encode(&self) -> Result<String> {
+        Ok(TextEncoder::new().encode_to_string(&self.registry.gather())?)
+    }
+
+    pub(crate) fn record(&self, origin: &InteractionOrigin, outcome: &str, duration: Duration) {
+        let kind = match origin.kind() {
+            InteractionKind::Command => "command",
+            InteractionKind::Autocomplete => "autocomplete",
+            InteractionKind::Component => "component",
+            InteractionKind::Modal => "modal",
+        };
+        let origin = match origin {
+            InteractionOrigin::Command(command_path)
+            | InteractionOrigin::Autocomplete(command_path) => command_path.to_string(),
+            InteractionOrigin::Component(Some(uuid)) | InteractionOrigin::Modal(Some(uuid)) => {
+                uuid.to_string()
+            }
+            InteractionOrigin::Component(None) | InteractionOrigin::Modal(None) => {
+                "unknown".to_string()
+            }
+        };
+        let labels = [kind, &origin, outcome];
+        self.interactions.with_label_values(&labels).inc();
+        self.durations
+            .with_label_values(&labels)
+            .observe(duration.as_secs_f64());
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Serves the metrics on every path of the listener until the bot stops.
+pub(crate) async fn serve(listener: TcpListener, metrics: Arc<Metrics>) -> Result<()> {
+    listener.set_nonblocking(true)?;
+    let addr = listener.local_addr()?;
+    let server = Server::from_tcp(listener)?.serve(make_service_fn(move |_| {
+        let metrics = metrics.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |_| {
+                let metrics = metrics.clone();
+                async move {
+                    Ok::<_, Infallible>(match metrics.encode() {
+                        Ok(body) => {
+                            let mut response = Response::new(Body::from(body));
+                            response.headers_mut().insert(
+                                CONTENT_TYPE,
+                                "text/plain; version=0.0.4".parse().expect("valid header"),
+                            );
+                            response
+                        }
+                        Err(error) => {
+                            error!("failed to encode metrics: {error}");
+                            status_response(hyper::StatusCode::INTERNAL_SERVER_ERROR)
+                        }
+                    })
+                }
+            }))
+        }
+    }));
+
+    info!("Serving metrics on {addr}");
+    Ok(server.await?)
+}
@@ -0,0 +1,185 @@
+use std::{pin::Pin, sync::Arc};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::Future;
+use serenity::{
+    builder::CreateInteractionResponse,
+    client::Context,
+    model::{
+        id::{ChannelId, GuildId},
+        user::User,
+    },
+};
+
+use crate::context::{command::CommandCtx, component::ComponentCtx, modal::ModalCtx};
+
+/// The context of any interaction that passes through [`Middleware`].
+pub enum InteractionCtx {
+    Command(CommandCtx),
+    Component(ComponentCtx),
+    Modal(ModalCtx),
+}
+
+impl InteractionCtx {
+    pub fn bot(&self) -> &Context {
+        match self {
+            InteractionCtx::Command(ctx) => &ctx.bot,
+            InteractionCtx::Component(ctx) => &ctx.bot,
+            InteractionCtx::Modal(ctx) => &ctx.bot,
+        }
+    }
+
+    /// Shared data that was registered using [`Bot::with_data`](crate::bot::Bot::with_data).
+    pub fn data<T: Send + Sync + 'static>(&self) -> &T {
+        match self {
+            InteractionCtx::Command(ctx) => ctx.data(),
+            InteractionCtx::Component(ctx) => ctx.data(),
+            InteractionCtx::Modal(ctx) => ctx.data(),
+        }
+    }
+
+    pub fn user(&self) -> &User {
+        match self {
+            InteractionCtx::Command(ctx) => &ctx.interaction.user,
+            InteractionCtx::Component(ctx) => &ctx.interaction.user,
+            InteractionCtx::Modal(ctx) => &ctx.interaction.user,
+        }
+    }
+
+    pub fn locale(&self) -> &str {
+        match self {
+            InteractionCtx::Command(ctx) => &ctx.interaction.locale,
+            InteractionCtx::Component(ctx) => &ctx.interaction.locale,
+            InteractionCtx::Modal(ctx) => &ctx.interaction.locale,
+        }
+    }
+
+    pub fn guild_id(&self) -> Option<GuildId> {
+        match self {
+            InteractionCtx::Command(ctx) => ctx.interaction.guild_id,
+            InteractionCtx::Component(ctx) => ctx.interaction.guild_id,
+            InteractionCtx::Modal(ctx) => ctx.interaction.guild_id,
+        }
+    }
+
+    pub fn channel_id(&self) -> ChannelId {
+        match self {
+            InteractionCtx::Command(ctx) => ctx.interaction.channel_id,
+            InteractionCtx::Component(ctx) => ctx.interaction.channel_id,
+            InteractionCtx::Modal(ctx) => ctx.interaction.channel_id,
+        }
+    }
+
+    /// Responds to the interaction, usually to short-circuit the rest of the middleware chain.
+    pub async fn respond<'a, F>(self, f: F) -> serenity::Result<()>
+    where
+        for<'b> F:
+            FnOnce(&'b mut CreateInteractionResponse<'a>) -> &'b mut CreateInteractionResponse<'a>,
+    {
+        match self {
+            InteractionCtx::Command(ctx) => ctx.respond(f).await.map(|_| ()),
+            InteractionCtx::Component(ctx) => ctx.respond(f).await.map(|_| ()),
+            InteractionCtx::Modal(ctx) => ctx.respond(f).await.map(|_| ()),
+        }
+    }
+}
+
+impl From<CommandCtx> for InteractionCtx {
+    fn from(ctx: CommandCtx) -> Self {
+        Self::Command(ctx)
+    }
+}
+
+impl From<ComponentCtx> for InteractionCtx {
+    fn from(ctx: ComponentCtx) -> Self {
+        Self::Component(ctx)
+    }
+}
+
+impl From<ModalCtx> for InteractionCtx {
+    fn from(ctx: ModalCtx) -> Self {
+        Self::Modal(ctx)
+    }
+}
+
+impl TryFrom<InteractionCtx> for CommandCtx {
+    type Error = InteractionCtx;
+
+    fn try_from(ctx: InteractionCtx) -> Result<Self, Self::Error> {
+        match ctx {
+            InteractionCtx::Command(ctx) => Ok(ctx),
+            ctx => Err(ctx),
+        }
+    }
+}
+
+impl TryFrom<InteractionCtx> for ComponentCtx {
+    type Error = InteractionCtx;
+
+    fn try_from(ctx: InteractionCtx) -> Result<Self, Self::Error> {
+        match ctx {
+            InteractionCtx::Component(ctx) => Ok(ctx),
+            ctx => Err(ctx),
+        }
+    }
+}
+
+impl TryFrom<InteractionCtx> for ModalCtx {
+    type Error = InteractionCtx;
+
+    fn try_from(ctx: InteractionCtx) -> Result<Self, Self::Error> {
+        match ctx {
+            InteractionCtx::Modal(ctx) => Ok(ctx),
+            ctx => Err(ctx),
+        }
+    }
+}
+
+/// Runs around every command, component interaction and modal submission.
+///
+/// Calling [`Next::run`] continues with the next middleware and eventually the actual handler,
+/// whose result is returned. Not calling it short-circuits the chain, in which case the
+/// middleware should respond to the interaction itself.
+#[async_trait]
+pub trait Middleware: Send + Sync {
+    async fn handle(&self, ctx: InteractionCtx, next: Next<'_>) -> Result<()>;
+}
+
+type Endpoint<'a> = Box<
+    dyn FnOnce(InteractionCtx) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> + Send + 'a,
+>;
+
+/// The remainder of a middleware chain.
+pub struct Next<'a> {
+    middlewares: &'a [Arc<dyn Middleware>],
+    endpoint: Endpoint<'a>,
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(middlewares: &'a [Arc<dyn Middleware>], endpoint: Endpoint<'a>) -> Self {
+        Self {
+            middlewares,
+            endpoint,
+        }
+    }
+
+    pub fn from_fn(
+        f: impl FnOnce(InteractionCtx) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>
+            + Send
+            + 'a,
+    ) -> Self {
+        Self::new(&[], Box::new(f))
+    }
+
+    pub async fn run(self, ctx: InteractionCtx) -> Result<()> {
+        match self.middlewares.split_first() {
+            Some((middleware, middlewares)) => {
+                middleware
+                    .handle(ctx, Next::new(middlewares, self.endpoint))
+                    .await
+            }
+            None => (self.endpoint)(ctx).await,
+        }
+    }
+}
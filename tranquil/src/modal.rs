@@ -176,10 +176,16 @@ impl RespondCtx for CommandCtx {
         for<'b> F: FnOnce(&'b mut CreateInteractionResponse<'a>) -> &'b mut CreateInteractionResponse<'a>
             + Send,
     {
-        self.interaction
-            .create_interaction_response(&self.bot, f)
-            .await?;
-        Ok(())
+        let mut response = CreateInteractionResponse::default();
+        f(&mut response);
+        self.initial_response
+            .respond(
+                &self.bot.http,
+                self.interaction.id,
+                &self.interaction.token,
+                response,
+            )
+            .await
     }
 }
 
@@ -190,9 +196,15 @@ impl RespondCtx for ComponentCtx {
         for<'b> F: FnOnce(&'b mut CreateInteractionResponse<'a>) -> &'b mut CreateInteractionResponse<'a>
             + Send,
     {
-        self.interaction
-            .create_interaction_response(&self.bot, f)
-            .await?;
-        Ok(())
+        let mut response = CreateInteractionResponse::default();
+        f(&mut response);
+        self.initial_response
+            .respond(
+                &self.bot.http,
+                self.interaction.id,
+                &self.interaction.token,
+                response,
+            )
+            .await
     }
 }
@@ -1,12 +1,21 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use async_trait::async_trait;
-use serenity::model::gateway::GatewayIntents;
+use serenity::{
+    client::Context,
+    model::{
+        event::Event,
+        gateway::{GatewayIntents, Ready},
+        guild::{Guild, UnavailableGuild},
+    },
+};
 pub use tranquil_macros::Module;
 use uuid::Uuid;
 
 use crate::{
+    bot::ApplicationCommandUpdate,
     command::CommandProvider,
     context::{component::ComponentCtx, modal::ModalCtx},
+    cooldown::Cooldown,
     l10n::{L10n, L10nLoadError},
 };
 
@@ -16,6 +25,13 @@ pub trait Module: CommandProvider + Send + Sync {
         GatewayIntents::empty()
     }
 
+    /// Where the commands of this module are registered, overriding the scope of the bot.
+    ///
+    /// Individual commands can override this again using e.g. `#[slash(scope = "global")]`.
+    fn scope(&self) -> Option<ApplicationCommandUpdate> {
+        None
+    }
+
     async fn l10n(&self) -> Result<L10n, L10nLoadError> {
         Ok(L10n::new())
     }
@@ -24,15 +40,49 @@ pub trait Module: CommandProvider + Send + Sync {
         &[]
     }
 
-    async fn interact(&self, _uuid: Uuid, _state: &str, _ctx: ComponentCtx) -> Result<()> {
-        panic!("module does not handle any interactions")
+    fn interaction_cooldown(&self, _uuid: Uuid) -> Option<Cooldown> {
+        None
+    }
+
+    async fn interact(&self, uuid: Uuid, _state: &str, _ctx: ComponentCtx) -> Result<()> {
+        bail!("module does not handle interactions with uuid {uuid}")
     }
 
     fn modal_uuids(&self) -> &'static [Uuid] {
         &[]
     }
 
-    async fn submit(&self, _uuid: Uuid, _state: &str, _ctx: ModalCtx) -> Result<()> {
-        panic!("module does not handle any modals")
+    async fn submit(&self, uuid: Uuid, _state: &str, _ctx: ModalCtx) -> Result<()> {
+        bail!("module does not handle modals with uuid {uuid}")
+    }
+
+    /// Intents required by the listeners of [`handle_events!`](crate::handle_events).
+    fn event_intents(&self) -> GatewayIntents {
+        GatewayIntents::empty()
+    }
+
+    /// Forwards gateway events to the listeners of [`handle_events!`](crate::handle_events).
+    async fn handle_event(&self, _bot: &Context, _event: &Event) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called every time the bot (re)connects, after application commands have been updated.
+    async fn on_ready(&self, _bot: &Context, _ready: &Ready) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called when a guild becomes available, which includes all guilds right after connecting.
+    async fn on_guild_create(&self, _bot: &Context, _guild: &Guild) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called when the bot leaves a guild or a guild becomes unavailable.
+    async fn on_guild_delete(&self, _bot: &Context, _guild: &UnavailableGuild) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called once when the bot stops.
+    async fn on_shutdown(&self) -> Result<()> {
+        Ok(())
     }
 }
@@ -59,20 +59,27 @@ impl_resolve! {
 }
 
 mod channel;
+mod command_options;
+mod constraints;
 mod error;
 mod integer;
 mod mentionable;
 mod number;
 mod option;
 mod string;
+mod subcommands;
 mod user;
 
 pub use channel::*;
+pub use command_options::*;
+pub use constraints::*;
 pub use error::*;
 pub use integer::*;
 pub use mentionable::*;
 pub use string::*;
+pub use subcommands::*;
 
+#[allow(clippy::result_large_err)]
 fn resolve_option(
     option: Option<CommandDataOption>,
 ) -> error::ResolveResult<CommandDataOptionValue> {
@@ -93,6 +100,17 @@ pub fn find_options<'a, I: IntoIterator<Item = &'a str>>(
     })
 }
 
+/// The name of the option that is currently being autocompleted, if any.
+pub fn focused_option(options: &[CommandDataOption]) -> Option<&str> {
+    options.iter().find_map(|option| {
+        if option.focused {
+            Some(option.name.as_str())
+        } else {
+            focused_option(&option.options)
+        }
+    })
+}
+
 pub fn resolve_command_options(mut options: Vec<CommandDataOption>) -> Vec<CommandDataOption> {
     if options.len() != 1 {
         options
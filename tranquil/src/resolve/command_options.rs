@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serenity::{
+    http::Http, model::application::interaction::application_command::CommandDataOption,
+};
+
+use super::InvalidParameters;
+use crate::command::CommandOption;
+
+pub use tranquil_macros::CommandOptions;
+
+/// A group of options that can be shared between commands using `#[flatten]`.
+///
+/// Each field becomes a separate option, named after the field unless it is renamed. Fields
+/// support the same attributes as the parameters of a `#[slash]` function, except for
+/// `#[autocomplete = ...]`.
+#[async_trait]
+pub trait CommandOptions: Sized {
+    /// The names of all options, in the same order as [`CommandOptions::options`].
+    fn option_names() -> Vec<&'static str>;
+    fn options() -> Vec<CommandOption>;
+
+    /// Resolves the options, which are given in the order of [`CommandOptions::option_names`].
+    async fn resolve(
+        http: Arc<Http>,
+        options: Vec<Option<CommandDataOption>>,
+    ) -> Result<Self, InvalidParameters>;
+}
@@ -0,0 +1,152 @@
+use serenity::{
+    builder::CreateApplicationCommandOption,
+    model::{
+        application::{
+            command::CommandOptionType,
+            interaction::application_command::{CommandDataOption, CommandDataOptionValue},
+        },
+        channel::ChannelType,
+    },
+};
+
+use super::{Resolve, ResolveContext, ResolveError, ResolveResult};
+
+/// Constraints on a single option, set using parameter attributes like `#[min = 1]`.
+///
+/// Unlike `bounded_number!` and friends, these do not require a separate type. They are sent to
+/// Discord and checked again before the option is resolved.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct OptionConstraints {
+    pub min: Option<OptionBound>,
+    pub max: Option<OptionBound>,
+    pub min_length: Option<u16>,
+    pub max_length: Option<u16>,
+    pub channel_types: Option<&'static [ChannelType]>,
+}
+
+impl OptionConstraints {
+    pub fn describe(&self, kind: CommandOptionType, option: &mut CreateApplicationCommandOption) {
+        match kind {
+            CommandOptionType::Integer => {
+                if let Some(min) = self.min {
+                    option.min_int_value(min.min_integer());
+                }
+                if let Some(max) = self.max {
+                    option.max_int_value(max.max_integer());
+                }
+            }
+            CommandOptionType::Number => {
+                if let Some(min) = self.min {
+                    option.min_number_value(min.number());
+                }
+                if let Some(max) = self.max {
+                    option.max_number_value(max.number());
+                }
+            }
+            _ => {}
+        }
+        if let Some(min_length) = self.min_length {
+            option.min_length(min_length);
+        }
+        if let Some(max_length) = self.max_length {
+            option.max_length(max_length);
+        }
+        if let Some(channel_types) = self.channel_types {
+            option.channel_types(channel_types);
+        }
+    }
+
+    /// Checks the constraints against an option, which is fine if it is missing.
+    #[allow(clippy::result_large_err)]
+    pub fn check(&self, option: Option<&CommandDataOption>) -> ResolveResult<()> {
+        match option.and_then(|option| option.resolved.as_ref()) {
+            Some(CommandDataOptionValue::Integer(value)) if !self.contains_integer(*value) => {
+                Err(ResolveError::IntegerRangeError)
+            }
+            Some(CommandDataOptionValue::Number(value)) if !self.contains_number(*value) => {
+                Err(ResolveError::NumberRangeError)
+            }
+            Some(CommandDataOptionValue::String(value)) => {
+                let length = value.chars().count();
+                let min_ok = self.min_length.is_none_or(|min| length >= usize::from(min));
+                let max_ok = self.max_length.is_none_or(|max| length <= usize::from(max));
+                if min_ok && max_ok {
+                    Ok(())
+                } else {
+                    Err(ResolveError::StringLengthError)
+                }
+            }
+            Some(CommandDataOptionValue::Channel(channel))
+                if self
+                    .channel_types
+                    .is_some_and(|channel_types| !channel_types.contains(&channel.kind)) =>
+            {
+                Err(ResolveError::InvalidChannelType)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Resolves the option after checking it against the constraints.
+    pub async fn resolve<T: Resolve>(self, ctx: ResolveContext) -> ResolveResult<T> {
+        self.check(ctx.option.as_ref())?;
+        T::resolve(ctx).await
+    }
+
+    fn contains_integer(&self, value: i64) -> bool {
+        self.min.is_none_or(|min| value >= min.min_integer())
+            && self.max.is_none_or(|max| value <= max.max_integer())
+    }
+
+    fn contains_number(&self, value: f64) -> bool {
+        self.min.is_none_or(|min| value >= min.number())
+            && self.max.is_none_or(|max| value <= max.number())
+    }
+}
+
+/// The value of `#[min]` or `#[max]`, which keeps integers exact instead of going through `f64`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OptionBound {
+    Integer(i64),
+    Number(f64),
+}
+
+impl OptionBound {
+    /// Rounds up, so that a fractional minimum of an integer option stays inclusive.
+    fn min_integer(self) -> i64 {
+        match self {
+            Self::Integer(value) => value,
+            Self::Number(value) => value.ceil() as i64,
+        }
+    }
+
+    /// Rounds down, so that a fractional maximum of an integer option stays inclusive.
+    fn max_integer(self) -> i64 {
+        match self {
+            Self::Integer(value) => value,
+            Self::Number(value) => value.floor() as i64,
+        }
+    }
+
+    fn number(self) -> f64 {
+        match self {
+            Self::Integer(value) => value as f64,
+            Self::Number(value) => value,
+        }
+    }
+}
+
+macro_rules! impl_option_bound_from {
+    ($variant:ident: $($T:ty),*) => {
+        $(
+            impl From<$T> for OptionBound {
+                fn from(value: $T) -> Self {
+                    Self::$variant(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_option_bound_from!(Integer: i8, i16, i32, i64, u8, u16, u32);
+impl_option_bound_from!(Number: f32, f64);
@@ -37,3 +37,42 @@ impl From<Infallible> for ResolveError {
 }
 
 pub type ResolveResult<T> = Result<T, ResolveError>;
+
+#[derive(Debug, Error)]
+#[error("{name}: {error}")]
+pub struct InvalidParameter {
+    pub name: String,
+    pub error: ResolveError,
+}
+
+#[derive(Debug, Default, Error)]
+pub struct InvalidParameters(pub Vec<InvalidParameter>);
+
+impl InvalidParameters {
+    pub fn check<T>(&mut self, name: &str, result: ResolveResult<T>) -> Option<T> {
+        result
+            .map_err(|error| {
+                self.0.push(InvalidParameter {
+                    name: name.to_string(),
+                    error,
+                })
+            })
+            .ok()
+    }
+
+    /// Like [`InvalidParameters::check`], but for a group of options that can fail individually.
+    pub fn check_options<T>(&mut self, result: Result<T, InvalidParameters>) -> Option<T> {
+        result
+            .map_err(|invalid_parameters| self.0.extend(invalid_parameters.0))
+            .ok()
+    }
+}
+
+impl std::fmt::Display for InvalidParameters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.iter().try_fold((), |_, error| {
+            error.fmt(f)?;
+            writeln!(f)
+        })
+    }
+}
@@ -0,0 +1,67 @@
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use serenity::{
+    http::Http, model::application::interaction::application_command::CommandDataOption,
+};
+
+use super::InvalidParameters;
+use crate::command::CommandOption;
+
+pub use tranquil_macros::Subcommands;
+
+/// An enum of subcommands that is handled by a single `#[slash]` function using `#[subcommands]`.
+///
+/// Variants with named fields become subcommands with one option per field, which support the same
+/// attributes as fields of [`CommandOptions`](super::CommandOptions). Variants with a single
+/// unnamed field become subcommand groups, which requires the field to be a [`SubcommandGroup`].
+pub trait Subcommands: Sized + Send + 'static {
+    fn subcommands() -> Vec<Subcommand<Self>>;
+}
+
+/// [`Subcommands`] without any groups, which can be used as a group themselves.
+///
+/// Derived automatically, since Discord does not allow nesting groups.
+pub trait SubcommandGroup: Subcommands {}
+
+pub type SubcommandResolver<T> = Box<
+    dyn Fn(
+            Arc<Http>,
+            Vec<CommandDataOption>,
+        ) -> Pin<Box<dyn Future<Output = Result<T, InvalidParameters>> + Send>>
+        + Send
+        + Sync,
+>;
+
+pub struct Subcommand<T> {
+    pub group: Option<&'static str>,
+    /// Used for the group if the [`L10n`](crate::l10n::L10n) has no description for it.
+    pub group_description: Option<&'static str>,
+    pub name: &'static str,
+    /// Used if the [`L10n`](crate::l10n::L10n) has no description for the default locale.
+    pub description: Option<&'static str>,
+    pub options: Vec<CommandOption>,
+    /// Resolves the subcommand from the options of the subcommand itself.
+    pub resolver: SubcommandResolver<T>,
+}
+
+/// The subcommands of a group, resolved into `U` using the `variant` that holds the group.
+pub fn subcommand_group<T: SubcommandGroup, U: Subcommands>(
+    group: &'static str,
+    group_description: Option<&'static str>,
+    variant: fn(T) -> U,
+) -> impl Iterator<Item = Subcommand<U>> {
+    T::subcommands().into_iter().map(move |subcommand| {
+        let resolver = subcommand.resolver;
+        Subcommand {
+            group: Some(group),
+            group_description,
+            name: subcommand.name,
+            description: subcommand.description,
+            options: subcommand.options,
+            resolver: Box::new(move |http, options| {
+                let subcommand = resolver(http, options);
+                Box::pin(async move { subcommand.await.map(variant) })
+            }),
+        }
+    })
+}
@@ -8,8 +8,8 @@ use serenity::{builder::CreateSelectMenu, model::channel::ReactionType};
 use uuid::Uuid;
 
 use crate::{
-    context::component::ComponentCtx, custom_id::custom_id_encode, interaction::Interact,
-    module::Module,
+    context::component::ComponentCtx, cooldown::Cooldown, custom_id::custom_id_encode,
+    interaction::Interact, module::Module,
 };
 
 pub struct SelectMenu {
@@ -170,6 +170,7 @@ impl<T: SelectMenuChoice> Default for SelectHandler<T> {
 #[async_trait]
 impl<T: Select + Sync> Interact for SelectHandler<T> {
     const UUID: Uuid = T::UUID;
+    const COOLDOWN: Option<Cooldown> = T::COOLDOWN;
 
     type Module = T::Module;
 
@@ -186,6 +187,8 @@ impl<T: Select + Sync> Interact for SelectHandler<T> {
 
 #[async_trait]
 pub trait Select: SelectMenuChoice {
+    const COOLDOWN: Option<Cooldown> = None;
+
     async fn select(self, module: &Self::Module, ctx: ComponentCtx) -> Result<()>;
 }
 
@@ -217,6 +220,7 @@ where
     EnumSet<T>: Send,
 {
     const UUID: Uuid = T::UUID;
+    const COOLDOWN: Option<Cooldown> = T::COOLDOWN;
 
     type Module = T::Module;
 
@@ -227,6 +231,8 @@ where
 
 #[async_trait]
 pub trait MultiSelect: SelectMenuOptions {
+    const COOLDOWN: Option<Cooldown> = None;
+
     async fn multi_select(
         values: EnumSet<Self>,
         module: &Self::Module,
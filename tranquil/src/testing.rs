@@ -0,0 +1,1018 @@
+//! An in-process harness to test modules without connecting to Discord.
+//!
+//! Interactions are dispatched straight to the [`Bot`], which passes its initial response back
+//! directly, while all other requests to the REST API are answered by a local stand-in. Both are
+//! captured for later assertions.
+
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    net::{Ipv4Addr, TcpListener},
+    sync::{
+        atomic::{self, AtomicU64},
+        Arc, Mutex,
+    },
+};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use hyper::{
+    body::to_bytes,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use serde_json::{json, Value};
+use serenity::{
+    client::{bridge::gateway::ShardMessenger, Context, EventHandler},
+    futures::channel::mpsc,
+    http::{Http, HttpBuilder},
+    model::{
+        application::interaction::Interaction,
+        id::{ChannelId, GuildId, MessageId, UserId},
+    },
+    prelude::{RwLock, TypeMap},
+};
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+use crate::{
+    bot::Bot,
+    command::CommandPath,
+    custom_id::custom_id_encode,
+    error_handler::{ErrorHandler, InteractionError},
+    initial_response::InitialResponse,
+    interaction::Interact,
+    modal::OpenModal,
+    utils::{json_response, status_response},
+};
+
+/// A [`Bot`] that handles synthetic interactions and captures what it sends back.
+///
+/// ```ignore
+/// let bot = TestBot::new(Bot::new().register(PingModule)).await?;
+/// let outcome = bot.command("ping").run().await;
+/// assert_eq!(outcome.contents(), ["Pong!"]);
+/// ```
+pub struct TestBot {
+    bot: Arc<Bot>,
+    http: Arc<Http>,
+    stand_in: Arc<StandIn>,
+    errors: Arc<Mutex<HashMap<String, String>>>,
+    next_interaction_id: AtomicU64,
+    server: JoinHandle<()>,
+}
+
+impl TestBot {
+    pub async fn new(mut bot: Bot) -> Result<Self> {
+        bot.load().await?;
+
+        let errors = Arc::<Mutex<HashMap<String, String>>>::default();
+        let inner = bot.take_error_handler();
+        let bot = bot.on_error(CapturingErrorHandler {
+            inner,
+            errors: errors.clone(),
+        });
+
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+        listener.set_nonblocking(true)?;
+        // Serenity only sends requests to a proxy with its rate limiter disabled. This is fine,
+        // since the stand-in takes the place of Discord and nothing is ever sent there.
+        let http = HttpBuilder::new("test")
+            .application_id(APPLICATION_ID)
+            .proxy(format!("http://{}", listener.local_addr()?))?
+            .ratelimiter_disabled(true)
+            .build();
+
+        let stand_in = Arc::new(StandIn::default());
+        let server = Server::from_tcp(listener)?.serve(make_service_fn({
+            let stand_in = stand_in.clone();
+            move |_| {
+                let stand_in = stand_in.clone();
+                async move {
+                    Ok::<_, Infallible>(service_fn(move |request| stand_in.clone().handle(request)))
+                }
+            }
+        }));
+
+        Ok(Self {
+            bot: bot.into_shared(),
+            http: Arc::new(http),
+            stand_in,
+            errors,
+            next_interaction_id: AtomicU64::new(1),
+            server: tokio::spawn(async move {
+                if let Err(error) = server.await {
+                    warn!("test server stopped: {error}");
+                }
+            }),
+        })
+    }
+
+    /// Invokes a slash command, whose options can be added afterwards.
+    ///
+    /// Top-level commands can be given by name, e.g. `"ping"`, while subcommands need a full
+    /// [`CommandPath`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path belongs to a context-menu command, which are invoked using
+    /// [`user_command`](Self::user_command) and [`message_command`](Self::message_command).
+    pub fn command(&self, command_path: impl Into<CommandPath>) -> TestInteraction<'_> {
+        let path = command_path.into();
+        assert!(path.is_slash(), "not a slash command: {path}");
+        self.interaction(TestInteractionData::Command {
+            path,
+            options: vec![],
+            users: Default::default(),
+        })
+    }
+
+    /// Invokes a user context-menu command on the given user.
+    pub fn user_command(&self, name: impl Into<String>, user_id: UserId) -> TestInteraction<'_> {
+        self.interaction(TestInteractionData::UserCommand {
+            name: name.into(),
+            user_id,
+        })
+    }
+
+    /// Invokes a message context-menu command on a message with the given content.
+    pub fn message_command(
+        &self,
+        name: impl Into<String>,
+        content: impl Into<String>,
+    ) -> TestInteraction<'_> {
+        self.interaction(TestInteractionData::MessageCommand {
+            name: name.into(),
+            content: content.into(),
+        })
+    }
+
+    /// Clicks a button that was created for the given [`Interact`] value.
+    pub fn click<T: Interact>(&self, interact: &T) -> TestInteraction<'_> {
+        self.interaction(TestInteractionData::Component {
+            custom_id: format!("{} {}", T::UUID.simple(), custom_id_encode(interact)),
+            component_type: 2,
+            values: vec![],
+        })
+    }
+
+    /// Selects the given values of a select menu that was created for the [`Interact`] value.
+    pub fn select<T: Interact>(
+        &self,
+        interact: &T,
+        values: impl IntoIterator<Item = impl Into<String>>,
+    ) -> TestInteraction<'_> {
+        self.interaction(TestInteractionData::Component {
+            custom_id: format!("{} {}", T::UUID.simple(), custom_id_encode(interact)),
+            component_type: 3,
+            values: values.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    /// Submits a modal that was opened for the given value with one value per text input.
+    pub fn submit<T: OpenModal>(
+        &self,
+        modal: &T,
+        values: impl IntoIterator<Item = impl Into<String>>,
+    ) -> TestInteraction<'_> {
+        self.interaction(TestInteractionData::Modal {
+            custom_id: format!("{} {}", T::UUID.simple(), custom_id_encode(modal)),
+            values: values.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    fn interaction(&self, data: TestInteractionData) -> TestInteraction<'_> {
+        TestInteraction {
+            test_bot: self,
+            data,
+            user_id: UserId(1),
+            guild_id: Some(GuildId(1)),
+            channel_id: ChannelId(1),
+            locale: "en-US".to_owned(),
+        }
+    }
+}
+
+impl Drop for TestBot {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+/// A synthetic interaction, which by default is sent by user `1` in channel `1` of guild `1`.
+pub struct TestInteraction<'a> {
+    test_bot: &'a TestBot,
+    data: TestInteractionData,
+    user_id: UserId,
+    guild_id: Option<GuildId>,
+    channel_id: ChannelId,
+    locale: String,
+}
+
+enum TestInteractionData {
+    Command {
+        path: CommandPath,
+        options: Vec<Value>,
+        users: serde_json::Map<String, Value>,
+    },
+    UserCommand {
+        name: String,
+        user_id: UserId,
+    },
+    MessageCommand {
+        name: String,
+        content: String,
+    },
+    Component {
+        custom_id: String,
+        component_type: u8,
+        values: Vec<String>,
+    },
+    Modal {
+        custom_id: String,
+        values: Vec<String>,
+    },
+}
+
+impl TestInteraction<'_> {
+    pub fn user(mut self, user_id: UserId) -> Self {
+        self.user_id = user_id;
+        self
+    }
+
+    pub fn guild(mut self, guild_id: GuildId) -> Self {
+        self.guild_id = Some(guild_id);
+        self
+    }
+
+    /// Sends the interaction from a direct message instead of a guild.
+    pub fn dm(mut self) -> Self {
+        self.guild_id = None;
+        self
+    }
+
+    pub fn channel(mut self, channel_id: ChannelId) -> Self {
+        self.channel_id = channel_id;
+        self
+    }
+
+    pub fn locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = locale.into();
+        self
+    }
+
+    /// Adds an option to a command.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this interaction is not a command.
+    pub fn option(mut self, name: impl Into<String>, value: impl Into<TestOption>) -> Self {
+        let TestInteractionData::Command { options, users, .. } = &mut self.data else {
+            panic!("only slash commands can have options");
+        };
+        let (kind, value) = match value.into() {
+            TestOption::String(value) => (3, json!(value)),
+            TestOption::Integer(value) => (4, json!(value)),
+            TestOption::Boolean(value) => (5, json!(value)),
+            TestOption::User(user_id) => {
+                users.insert(user_id.to_string(), user_json(user_id));
+                (6, json!(user_id.to_string()))
+            }
+            TestOption::Number(value) => (10, json!(value)),
+        };
+        options.push(json!({ "name": name.into(), "type": kind, "value": value }));
+        self
+    }
+
+    /// Dispatches the interaction and waits for its handler to finish.
+    pub async fn run(self) -> TestOutcome {
+        let test_bot = self.test_bot;
+        let id = test_bot
+            .next_interaction_id
+            .fetch_add(1, atomic::Ordering::Relaxed);
+        let token = format!("test-token-{id}");
+        let interaction = self.into_json(id, &token);
+        let interaction = serde_json::from_value::<Interaction>(interaction)
+            .expect("synthetic interaction should be valid");
+
+        let (shard, _) = mpsc::unbounded();
+        let bot = Context {
+            data: Arc::new(RwLock::new(TypeMap::new())),
+            shard: ShardMessenger::new(shard),
+            shard_id: 0,
+            http: test_bot.http.clone(),
+        };
+        let (initial_response, mut initial_response_receiver) = InitialResponse::local();
+        Arc::new(initial_response)
+            .scope(test_bot.bot.interaction_create(bot, interaction))
+            .await;
+
+        let mut responses = test_bot
+            .stand_in
+            .responses
+            .lock()
+            .expect("responses should not be poisoned")
+            .remove(&token)
+            .unwrap_or_default();
+        // Nothing else can be sent before the initial response.
+        if let Ok(response) = initial_response_receiver.try_recv() {
+            responses.insert(0, TestResponse::Response(response));
+        }
+
+        TestOutcome {
+            responses,
+            error: test_bot
+                .errors
+                .lock()
+                .expect("errors should not be poisoned")
+                .remove(&token),
+        }
+    }
+
+    fn into_json(self, id: u64, token: &str) -> Value {
+        let (kind, data) = match self.data {
+            TestInteractionData::Command {
+                path,
+                options,
+                users,
+            } => {
+                let options = match &path {
+                    CommandPath::Subcommand { subcommand, .. } => {
+                        vec![json!({ "name": subcommand, "type": 1, "options": options })]
+                    }
+                    CommandPath::Grouped {
+                        group, subcommand, ..
+                    } => vec![json!({
+                        "name": group,
+                        "type": 2,
+                        "options": [{ "name": subcommand, "type": 1, "options": options }],
+                    })],
+                    _ => options,
+                };
+                let data = json!({
+                    "id": "1",
+                    "name": path.name(),
+                    "type": 1,
+                    "options": options,
+                    "resolved": { "users": users },
+                });
+                (2, data)
+            }
+            TestInteractionData::UserCommand { name, user_id } => {
+                let mut resolved = json!({
+                    "users": { user_id.to_string(): user_json(user_id) },
+                });
+                if self.guild_id.is_some() {
+                    resolved["members"] = json!({ user_id.to_string(): member_json() });
+                }
+                let data = json!({
+                    "id": "1",
+                    "name": name,
+                    "type": 2,
+                    "target_id": user_id.to_string(),
+                    "resolved": resolved,
+                });
+                (2, data)
+            }
+            TestInteractionData::MessageCommand { name, content } => {
+                let message_id = MessageId(1);
+                let data = json!({
+                    "id": "1",
+                    "name": name,
+                    "type": 3,
+                    "target_id": message_id.to_string(),
+                    "resolved": {
+                        "messages": {
+                            message_id.to_string():
+                                message_json(message_id, &json!({ "content": content })),
+                        },
+                    },
+                });
+                (2, data)
+            }
+            TestInteractionData::Component {
+                custom_id,
+                component_type,
+                values,
+            } => {
+                let data = json!({
+                    "custom_id": custom_id,
+                    "component_type": component_type,
+                    "values": values,
+                });
+                (3, data)
+            }
+            TestInteractionData::Modal { custom_id, values } => {
+                let components = values
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, value)| {
+                        json!({
+                            "type": 1,
+                            "components": [{ "type": 4, "custom_id": i.to_string(), "value": value }],
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                let data = json!({ "custom_id": custom_id, "components": components });
+                (5, data)
+            }
+        };
+
+        let mut interaction = json!({
+            "id": id.to_string(),
+            "application_id": APPLICATION_ID.to_string(),
+            "type": kind,
+            "data": data,
+            "channel_id": self.channel_id.to_string(),
+            "token": token,
+            "version": 1,
+            "locale": self.locale,
+        });
+
+        if kind == 3 {
+            interaction["message"] = message_json(MessageId(1), &Value::Null);
+        }
+
+        let user = user_json(self.user_id);
+        match self.guild_id {
+            Some(guild_id) => {
+                interaction["guild_id"] = json!(guild_id.to_string());
+                let mut member = member_json();
+                member["user"] = user;
+                interaction["member"] = member;
+            }
+            None => interaction["user"] = user,
+        }
+
+        interaction
+    }
+}
+
+/// The value of a command option for [`TestInteraction::option`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum TestOption {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+    User(UserId),
+    Number(f64),
+}
+
+impl From<&str> for TestOption {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_owned())
+    }
+}
+
+impl From<String> for TestOption {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<i64> for TestOption {
+    fn from(value: i64) -> Self {
+        Self::Integer(value)
+    }
+}
+
+impl From<bool> for TestOption {
+    fn from(value: bool) -> Self {
+        Self::Boolean(value)
+    }
+}
+
+impl From<UserId> for TestOption {
+    fn from(value: UserId) -> Self {
+        Self::User(value)
+    }
+}
+
+impl From<f64> for TestOption {
+    fn from(value: f64) -> Self {
+        Self::Number(value)
+    }
+}
+
+/// Everything that was sent in response to a [`TestInteraction`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TestOutcome {
+    /// The responses in the order they were sent.
+    pub responses: Vec<TestResponse>,
+    /// The error that was returned by the handler, including its context.
+    pub error: Option<String>,
+}
+
+impl TestOutcome {
+    /// The content of every message that was sent or edited in order.
+    pub fn contents(&self) -> Vec<&str> {
+        self.responses
+            .iter()
+            .filter_map(TestResponse::content)
+            .collect()
+    }
+}
+
+/// A single request to the REST API, with bodies as they would have been sent to Discord.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TestResponse {
+    /// The initial response, including its `type` and `data`.
+    Response(Value),
+    EditResponse(Value),
+    DeleteResponse,
+    Followup(Value),
+    EditFollowup(MessageId, Value),
+    DeleteFollowup(MessageId),
+}
+
+impl TestResponse {
+    /// The message that was sent or edited, if any.
+    pub fn message(&self) -> Option<&Value> {
+        match self {
+            TestResponse::Response(response) => response.get("data"),
+            TestResponse::EditResponse(message)
+            | TestResponse::Followup(message)
+            | TestResponse::EditFollowup(_, message) => Some(message),
+            TestResponse::DeleteResponse | TestResponse::DeleteFollowup(_) => None,
+        }
+    }
+
+    pub fn content(&self) -> Option<&str> {
+        self.message()?.get("content")?.as_str()
+    }
+
+    pub fn is_ephemeral(&self) -> bool {
+        const EPHEMERAL: u64 = 1 << 6;
+        self.message()
+            .and_then(|message| message.get("flags"))
+            .and_then(Value::as_u64)
+            .is_some_and(|flags| flags & EPHEMERAL != 0)
+    }
+}
+
+const APPLICATION_ID: u64 = 1;
+
+struct CapturingErrorHandler {
+    inner: Box<dyn ErrorHandler>,
+    errors: Arc<Mutex<HashMap<String, String>>>,
+}
+
+#[async_trait]
+impl ErrorHandler for CapturingErrorHandler {
+    async fn handle_error(&self, bot: Context, error: InteractionError) {
+        self.errors
+            .lock()
+            .expect("errors should not be poisoned")
+            .insert(
+                error.interaction.token().to_owned(),
+                format!("{:#}", error.error),
+            );
+        self.inner.handle_error(bot, error).await;
+    }
+}
+
+/// Answers requests to the REST API in place of Discord.
+#[derive(Default)]
+struct StandIn {
+    responses: Mutex<HashMap<String, Vec<TestResponse>>>,
+    next_message_id: AtomicU64,
+}
+
+impl StandIn {
+    async fn handle(self: Arc<Self>, request: Request<Body>) -> Result<Response<Body>, Infallible> {
+        let method = request.method().clone();
+        let path = request.uri().path().to_owned();
+        let body = to_bytes(request.into_body()).await.unwrap_or_default();
+        let body = serde_json::from_slice(&body).unwrap_or(Value::Null);
+
+        let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+        let segments = match segments.as_slice() {
+            ["api", _version, segments @ ..] => segments,
+            segments => segments,
+        };
+
+        Ok(match (method, segments) {
+            (Method::POST, ["interactions", _id, token, "callback"]) => {
+                self.capture(token, TestResponse::Response(body));
+                status_response(StatusCode::NO_CONTENT)
+            }
+            (Method::POST, ["webhooks", _application_id, token]) => {
+                // Message id 1 is reserved for the initial response.
+                let message_id =
+                    MessageId(self.next_message_id.fetch_add(1, atomic::Ordering::Relaxed) + 2);
+                let response = message(message_id, &body);
+                self.capture(token, TestResponse::Followup(body));
+                response
+            }
+            (method, ["webhooks", _application_id, token, "messages", message_id]) => {
+                let message_id = match *message_id {
+                    "@original" => None,
+                    message_id => match message_id.parse() {
+                        Ok(message_id) => Some(MessageId(message_id)),
+                        Err(_) => return Ok(status_response(StatusCode::NOT_FOUND)),
+                    },
+                };
+                match (method, message_id) {
+                    (Method::GET, message_id) => {
+                        message(message_id.unwrap_or(MessageId(1)), &Value::Null)
+                    }
+                    (Method::PATCH, None) => {
+                        let response = message(MessageId(1), &body);
+                        self.capture(token, TestResponse::EditResponse(body));
+                        response
+                    }
+                    (Method::PATCH, Some(message_id)) => {
+                        let response = message(message_id, &body);
+                        self.capture(token, TestResponse::EditFollowup(message_id, body));
+                        response
+                    }
+                    (Method::DELETE, None) => {
+                        self.capture(token, TestResponse::DeleteResponse);
+                        status_response(StatusCode::NO_CONTENT)
+                    }
+                    (Method::DELETE, Some(message_id)) => {
+                        self.capture(token, TestResponse::DeleteFollowup(message_id));
+                        status_response(StatusCode::NO_CONTENT)
+                    }
+                    _ => status_response(StatusCode::METHOD_NOT_ALLOWED),
+                }
+            }
+            (method, _) => {
+                warn!("unsupported request in test: {method} {path}");
+                status_response(StatusCode::NOT_FOUND)
+            }
+        })
+    }
+
+    fn capture(&self, token: &str, response: TestResponse) {
+        self.responses
+            .lock()
+            .expect("responses should not be poisoned")
+            .entry(token.to_owned())
+            .or_default()
+            .push(response);
+    }
+}
+
+fn user_json(user_id: UserId) -> Value {
+    json!({
+        "id": user_id.to_string(),
+        "username": format!("user{user_id}"),
+        "discriminator": "0000",
+        "avatar": null,
+    })
+}
+
+fn member_json() -> Value {
+    json!({
+        "roles": [],
+        "joined_at": null,
+        "deaf": false,
+        "mute": false,
+    })
+}
+
+/// A minimal message, which takes its content from the request, if any.
+fn message_json(message_id: MessageId, request: &Value) -> Value {
+    json!({
+        "id": message_id.to_string(),
+        "channel_id": "1",
+        "author": {
+            "id": APPLICATION_ID.to_string(),
+            "username": "bot",
+            "discriminator": "0000",
+            "avatar": null,
+            "bot": true,
+        },
+        "content": request.get("content").and_then(Value::as_str).unwrap_or_default(),
+        "timestamp": "2015-05-13T00:00:00Z",
+        "edited_timestamp": null,
+        "tts": false,
+        "mention_everyone": false,
+        "mentions": [],
+        "mention_roles": [],
+        "attachments": [],
+        "embeds": [],
+        "pinned": false,
+        "type": 0,
+    })
+}
+
+fn message(message_id: MessageId, request: &Value) -> Response<Body> {
+    json_response(message_json(message_id, request).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use anyhow::bail;
+    use serde::{Deserialize, Serialize};
+    use serenity::model::application::{
+        component::ActionRowComponent, interaction::InteractionResponseType,
+    };
+    use uuid::{uuid, Uuid};
+
+    use super::*;
+    use crate::{
+        auto_defer::AutoDefer,
+        button::Button,
+        context::{command::CommandCtx, component::ComponentCtx, modal::ModalCtx},
+        custom_id::custom_id_decode,
+        handle_interactions,
+        macros::{command_provider, slash},
+        modal::ModalResponse,
+        module::Module,
+    };
+
+    struct EchoModule;
+
+    #[async_trait]
+    impl Module for EchoModule {
+        handle_interactions![EchoButton, SlowButton];
+
+        fn modal_uuids(&self) -> &'static [Uuid] {
+            &[EchoModal::UUID]
+        }
+
+        async fn submit(&self, _uuid: Uuid, state: &str, ctx: ModalCtx) -> Result<()> {
+            let modal = custom_id_decode::<EchoModal>(state)?;
+            let content = match &ctx.interaction.data.components[0].components[0] {
+                ActionRowComponent::InputText(text) => format!("{}{}", modal.prefix, text.value),
+                _ => bail!("expected a text input"),
+            };
+            ctx.respond(|response| {
+                response.interaction_response_data(|data| data.content(content))
+            })
+            .await?;
+            Ok(())
+        }
+    }
+
+    #[command_provider]
+    impl EchoModule {
+        #[slash]
+        async fn echo(&self, ctx: CommandCtx, text: String, times: i64) -> Result<()> {
+            if times < 1 {
+                bail!("cannot echo {times} times");
+            }
+
+            let ctx = ctx
+                .respond(|response| {
+                    response.interaction_response_data(|data| {
+                        data.content(&text).components(|components| {
+                            components.create_action_row(|row| {
+                                row.add_button(
+                                    Button::text("Again")
+                                        .create(&EchoButton { text: text.clone() }),
+                                )
+                            })
+                        })
+                    })
+                })
+                .await?;
+
+            for _ in 1..times {
+                ctx.followups()
+                    .create(|followup| followup.content(&text))
+                    .await?;
+            }
+
+            Ok(())
+        }
+
+        #[slash]
+        async fn fail(&self, ctx: CommandCtx, after: String) -> Result<()> {
+            match after.as_str() {
+                "defer" => {
+                    ctx.defer().await?;
+                }
+                "respond" => {
+                    ctx.respond(|response| {
+                        response.interaction_response_data(|data| data.content("responded"))
+                    })
+                    .await?;
+                }
+                _ => {}
+            }
+            bail!("failed after {after}")
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct EchoButton {
+        text: String,
+    }
+
+    #[async_trait]
+    impl Interact for EchoButton {
+        const UUID: Uuid = uuid!("6f1c1c3e-5a0e-4d5b-8f6a-2a1d0c9b8e7f");
+
+        type Module = EchoModule;
+
+        async fn interact(self, _module: &Self::Module, ctx: ComponentCtx) -> Result<()> {
+            ctx.respond(|response| {
+                response.interaction_response_data(|data| data.content(self.text).ephemeral(true))
+            })
+            .await?;
+            Ok(())
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct SlowButton {
+        update: bool,
+    }
+
+    #[async_trait]
+    impl Interact for SlowButton {
+        const UUID: Uuid = uuid!("3d2c1b0a-9f8e-4d7c-8b6a-5f4e3d2c1b0a");
+
+        type Module = EchoModule;
+
+        async fn interact(self, _module: &Self::Module, ctx: ComponentCtx) -> Result<()> {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            ctx.respond(|response| {
+                if self.update {
+                    response.kind(InteractionResponseType::UpdateMessage);
+                }
+                response.interaction_response_data(|data| data.content("slow"))
+            })
+            .await?;
+            Ok(())
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct EchoModal {
+        prefix: String,
+    }
+
+    struct EchoModalResponse;
+
+    impl ModalResponse for EchoModalResponse {}
+
+    #[async_trait]
+    impl OpenModal for EchoModal {
+        const UUID: Uuid = uuid!("0b7e5d2c-3f4a-4e6b-9c8d-1a2b3c4d5e6f");
+
+        type Module = EchoModule;
+        type Response = EchoModalResponse;
+
+        async fn submit(&self, _module: &Self::Module, _response: Self::Response) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    async fn test_bot() -> TestBot {
+        TestBot::new(Bot::new().register(EchoModule)).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn captures_command_responses() {
+        let bot = test_bot().await;
+        let outcome = bot
+            .command("echo")
+            .option("text", "hi")
+            .option("times", 3)
+            .run()
+            .await;
+        assert_eq!(outcome.error, None);
+        assert_eq!(outcome.contents(), ["hi", "hi", "hi"]);
+        assert!(matches!(outcome.responses[0], TestResponse::Response(_)));
+        assert!(matches!(outcome.responses[1], TestResponse::Followup(_)));
+    }
+
+    #[tokio::test]
+    async fn captures_command_errors() {
+        let bot = test_bot().await;
+        let outcome = bot
+            .command("echo")
+            .option("text", "hi")
+            .option("times", 0)
+            .run()
+            .await;
+        assert_eq!(outcome.error.as_deref(), Some("cannot echo 0 times"));
+        assert!(outcome.responses[0].is_ephemeral());
+    }
+
+    #[tokio::test]
+    async fn reports_errors_by_editing_deferred_responses() {
+        let bot = test_bot().await;
+        let outcome = bot.command("fail").option("after", "defer").run().await;
+        assert_eq!(outcome.error.as_deref(), Some("failed after defer"));
+        assert_eq!(outcome.responses.len(), 2);
+        let TestResponse::EditResponse(message) = &outcome.responses[1] else {
+            panic!("expected the deferred response to be edited");
+        };
+        assert!(message["embeds"].is_array());
+    }
+
+    #[tokio::test]
+    async fn skips_error_reports_after_responding() {
+        let bot = test_bot().await;
+        let outcome = bot.command("fail").option("after", "respond").run().await;
+        assert_eq!(outcome.error.as_deref(), Some("failed after respond"));
+        assert_eq!(outcome.contents(), ["responded"]);
+        assert_eq!(outcome.responses.len(), 1);
+    }
+
+    struct ContentErrorHandler;
+
+    #[async_trait]
+    impl ErrorHandler for ContentErrorHandler {
+        async fn handle_error(&self, bot: Context, error: InteractionError) {
+            let content = error.error.to_string();
+            error
+                .respond_or_edit(&bot, |response| {
+                    response.interaction_response_data(|data| data.content(content))
+                })
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn lets_custom_error_handlers_edit_deferred_responses() {
+        let bot = TestBot::new(
+            Bot::new()
+                .on_error(ContentErrorHandler)
+                .register(EchoModule),
+        )
+        .await
+        .unwrap();
+        let outcome = bot.command("fail").option("after", "defer").run().await;
+        let TestResponse::EditResponse(message) = &outcome.responses[1] else {
+            panic!("expected the deferred response to be edited");
+        };
+        assert_eq!(message["content"], "failed after defer");
+    }
+
+    #[tokio::test]
+    async fn captures_component_responses() {
+        let bot = test_bot().await;
+        let outcome = bot
+            .click(&EchoButton {
+                text: "again".to_owned(),
+            })
+            .run()
+            .await;
+        assert_eq!(outcome.error, None);
+        assert_eq!(outcome.contents(), ["again"]);
+        assert!(outcome.responses[0].is_ephemeral());
+    }
+
+    async fn auto_deferring_test_bot() -> TestBot {
+        let auto_defer = AutoDefer {
+            after: Duration::from_millis(10),
+            ephemeral: false,
+        };
+        TestBot::new(Bot::new().auto_defer(auto_defer).register(EchoModule))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn updates_message_of_auto_deferred_components() {
+        let bot = auto_deferring_test_bot().await;
+        let outcome = bot.click(&SlowButton { update: true }).run().await;
+        assert_eq!(outcome.error, None);
+        assert_eq!(
+            outcome.responses,
+            [
+                TestResponse::Response(json!({ "type": 6 })),
+                TestResponse::EditResponse(json!({ "content": "slow" })),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn sends_new_messages_of_auto_deferred_components_as_followups() {
+        let bot = auto_deferring_test_bot().await;
+        let outcome = bot.click(&SlowButton { update: false }).run().await;
+        assert_eq!(outcome.error, None);
+        assert_eq!(
+            outcome.responses,
+            [
+                TestResponse::Response(json!({ "type": 6 })),
+                TestResponse::Followup(json!({ "content": "slow" })),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn captures_modal_responses() {
+        let bot = test_bot().await;
+        let outcome = bot
+            .submit(
+                &EchoModal {
+                    prefix: "> ".to_owned(),
+                },
+                ["submitted"],
+            )
+            .run()
+            .await;
+        assert_eq!(outcome.error, None);
+        assert_eq!(outcome.contents(), ["> submitted"]);
+    }
+}
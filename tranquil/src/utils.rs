@@ -1,7 +1,8 @@
 use std::{env, ffi::OsStr};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serenity::model::id::GuildId;
+use tracing::error;
 
 use crate::bot::ApplicationCommandUpdate;
 
@@ -13,6 +14,16 @@ pub fn dotenv_if_exists() -> Result<(), dotenvy::Error> {
     }
 }
 
+/// Installs a tracing subscriber that prints to the console.
+///
+/// Does nothing if a global subscriber has already been set. The bot never installs a subscriber
+/// on its own, so this should be called at the start of `main` unless the application sets up
+/// its own.
+#[cfg(feature = "console")]
+pub fn init_console_logging() {
+    let _ = tracing_subscriber::fmt().try_init();
+}
+
 pub fn discord_token_from_env() -> Result<String, env::VarError> {
     discord_token_from_env_var("DISCORD_TOKEN")
 }
@@ -24,7 +35,7 @@ pub fn debug_guilds_from_env() -> Result<Option<ApplicationCommandUpdate>> {
 pub fn discord_token_from_env_var(key: impl AsRef<OsStr>) -> Result<String, env::VarError> {
     env::var(&key).map_err(|error| {
         if let env::VarError::NotPresent = error {
-            eprintln!(
+            error!(
                 "{} environment variable not found",
                 key.as_ref().to_string_lossy()
             );
@@ -36,9 +47,8 @@ pub fn discord_token_from_env_var(key: impl AsRef<OsStr>) -> Result<String, env:
 pub fn debug_guilds_from_env_var(
     key: impl AsRef<OsStr>,
 ) -> Result<Option<ApplicationCommandUpdate>> {
-    debug_guilds_from_env_var_silent(&key).inspect_err(|_| {
-        eprintln!("{} invalid", key.as_ref().to_string_lossy());
-    })
+    debug_guilds_from_env_var_silent(&key)
+        .with_context(|| format!("{} invalid", key.as_ref().to_string_lossy()))
 }
 
 fn debug_guilds_from_env_var_silent(
@@ -62,3 +72,26 @@ fn debug_guilds_from_env_var_silent(
         },
     }
 }
+
+/// An empty HTTP response with the given status.
+#[cfg(any(
+    feature = "http-interactions",
+    feature = "metrics",
+    feature = "testing"
+))]
+pub(crate) fn status_response(status: hyper::StatusCode) -> hyper::Response<hyper::Body> {
+    let mut response = hyper::Response::new(hyper::Body::empty());
+    *response.status_mut() = status;
+    response
+}
+
+/// An HTTP response with the given JSON body.
+#[cfg(any(feature = "http-interactions", feature = "testing"))]
+pub(crate) fn json_response(body: impl Into<hyper::Body>) -> hyper::Response<hyper::Body> {
+    let mut response = hyper::Response::new(body.into());
+    response.headers_mut().insert(
+        hyper::header::CONTENT_TYPE,
+        "application/json".parse().expect("valid header"),
+    );
+    response
+}